@@ -0,0 +1,166 @@
+//! Standalone polyphonic string ensemble
+//!
+//! `Sympathetic12`'s 12 strings are fixed pitch classes driven through
+//! the sympathetic matrix; `StringEnsemble` is a separate, self-contained
+//! pool of `KarplusStrong` voices addressed directly by frequency, each
+//! with its own ADSR amplitude envelope (attack/decay/sustain/release
+//! in milliseconds). Useful as a drop-in polyphonic instrument on its
+//! own, without any of the sympathetic-resonance machinery.
+
+use crate::string::KarplusStrong;
+use crate::voice::Envelope;
+use crate::SAMPLE_RATE;
+
+struct EnsembleVoice {
+    string: KarplusStrong,
+    envelope: Envelope,
+    frequency: f32,
+    active: bool,
+    /// Samples since this voice was triggered, for oldest-voice stealing
+    age: u32,
+}
+
+/// A self-contained pool of frequency-addressed plucked-string voices
+pub struct StringEnsemble {
+    voices: Vec<EnsembleVoice>,
+    attack_ms: f32,
+    decay_ms: f32,
+    sustain_level: f32,
+    release_ms: f32,
+}
+
+impl StringEnsemble {
+    /// Create an ensemble with `num_voices` voices, all initially silent
+    pub fn new(num_voices: usize) -> Self {
+        StringEnsemble {
+            voices: (0..num_voices.max(1))
+                .map(|_| EnsembleVoice {
+                    string: KarplusStrong::new(220.0, SAMPLE_RATE),
+                    envelope: Envelope::new(SAMPLE_RATE),
+                    frequency: 0.0,
+                    active: false,
+                    age: 0,
+                })
+                .collect(),
+            attack_ms: 2.0,
+            decay_ms: 150.0,
+            sustain_level: 0.7,
+            release_ms: 300.0,
+        }
+    }
+
+    /// Set the envelope attack time in milliseconds
+    pub fn set_attack_ms(&mut self, ms: f32) {
+        self.attack_ms = ms.max(0.0);
+    }
+
+    /// Set the envelope decay time in milliseconds
+    pub fn set_decay_ms(&mut self, ms: f32) {
+        self.decay_ms = ms.max(0.0);
+    }
+
+    /// Set the envelope sustain level (0-1)
+    pub fn set_sustain(&mut self, level: f32) {
+        self.sustain_level = level.clamp(0.0, 1.0);
+    }
+
+    /// Set the envelope release time in milliseconds
+    pub fn set_release_ms(&mut self, ms: f32) {
+        self.release_ms = ms.max(0.0);
+    }
+
+    /// Trigger a new voice at `freq`, stealing the quietest/oldest voice
+    /// if the pool is exhausted
+    pub fn note_on(&mut self, freq: f32, velocity: f32, position: f32) {
+        let idx = self.allocate_voice();
+        let voice = &mut self.voices[idx];
+
+        voice.frequency = freq;
+        voice.string.set_frequency(freq);
+        voice.string.pluck(velocity, position);
+
+        voice.envelope.set_attack(self.attack_ms / 1000.0, SAMPLE_RATE);
+        voice.envelope.set_decay(self.decay_ms / 1000.0, SAMPLE_RATE);
+        voice.envelope.set_sustain(self.sustain_level);
+        voice.envelope.set_release(self.release_ms / 1000.0, SAMPLE_RATE);
+        voice.envelope.trigger();
+
+        voice.active = true;
+        voice.age = 0;
+    }
+
+    /// Trigger several voices at once (a chord), mirroring
+    /// SuperCollider's array-valued event keys
+    pub fn note_on_chord(&mut self, freqs: &[f32], velocity: f32, position: f32) {
+        for &freq in freqs {
+            self.note_on(freq, velocity, position);
+        }
+    }
+
+    /// Release every active voice currently sounding `freq`, moving it
+    /// into the envelope's release segment, which fades its amplitude
+    /// linearly to zero over the release time instead of cutting it off
+    /// abruptly. The string's own damping is not changed.
+    pub fn note_off(&mut self, freq: f32) {
+        for voice in &mut self.voices {
+            if voice.active && (voice.frequency - freq).abs() < 0.01 {
+                voice.envelope.note_off();
+            }
+        }
+    }
+
+    /// Find a voice to (re)use: prefer a silent one, then the quietest
+    /// voice already in its release segment, then the oldest voice
+    /// overall
+    fn allocate_voice(&mut self) -> usize {
+        if let Some(idx) = self.voices.iter().position(|v| !v.active) {
+            return idx;
+        }
+
+        let mut quietest_releasing: Option<(usize, f32)> = None;
+        for (i, voice) in self.voices.iter().enumerate() {
+            if voice.envelope.is_releasing() {
+                let level = voice.envelope.level();
+                if quietest_releasing.map(|(_, l)| level < l).unwrap_or(true) {
+                    quietest_releasing = Some((i, level));
+                }
+            }
+        }
+        if let Some((idx, _)) = quietest_releasing {
+            return idx;
+        }
+
+        let mut oldest_idx = 0;
+        let mut oldest_age = 0;
+        for (i, voice) in self.voices.iter().enumerate() {
+            if voice.age >= oldest_age {
+                oldest_age = voice.age;
+                oldest_idx = i;
+            }
+        }
+        oldest_idx
+    }
+
+    /// Process one block of audio, summing every active voice's output
+    /// (scaled by its envelope) into `out`. Voices whose envelope has
+    /// fully decayed are marked inactive so they're free to be reused.
+    pub fn process(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            *sample = 0.0;
+        }
+
+        for voice in &mut self.voices {
+            if !voice.active {
+                continue;
+            }
+            for sample in out.iter_mut() {
+                let env_level = voice.envelope.advance();
+                *sample += voice.string.process(0.0) * env_level;
+            }
+            voice.age += out.len() as u32;
+            if voice.envelope.is_finished() {
+                voice.active = false;
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Continuously driven waveguide excitation models (reed, bow)
+//!
+//! Complements the one-shot plucked excitation in `string` with
+//! nonlinear junctions that keep injecting energy every sample, so a
+//! `KarplusStrong` delay line can be sustained with wind- or bow-like
+//! timbres instead of only decaying after a pluck. Both junctions are
+//! hard nonlinearities, so they run through an `Oversampler` to keep
+//! their aliased harmonics out of the audible band.
+
+use crate::oversample::Oversampler;
+
+/// Single-reed junction between a mouth pressure source and the bore
+///
+/// Models the reed as a nonlinear spring whose reflection coefficient
+/// depends on the pressure difference across it, as in the classic
+/// Karplus-Strong/Smith waveguide clarinet model.
+pub struct ReedModel {
+    /// Mouth (blowing) pressure envelope, 0-1. Replaces the one-shot
+    /// pluck: as long as this is nonzero the note keeps sounding.
+    pub pressure: f32,
+    /// Reed table offset (embouchure closure, ~0.7 at rest)
+    offset: f32,
+    /// Reed table slope (more negative = stiffer reed)
+    slope: f32,
+    oversampler: Oversampler,
+}
+
+impl ReedModel {
+    /// Create a new reed model at rest (silent until `pressure` is raised)
+    pub fn new() -> Self {
+        ReedModel {
+            pressure: 0.0,
+            offset: 0.7,
+            slope: -0.3,
+            oversampler: Oversampler::new(2),
+        }
+    }
+
+    /// Set the reed table shape (embouchure). `offset` is the rest
+    /// closure (~0.7), `slope` the reed stiffness (~-0.3, more negative
+    /// is stiffer).
+    pub fn set_embouchure(&mut self, offset: f32, slope: f32) {
+        self.offset = offset;
+        self.slope = slope;
+    }
+
+    /// Drive the junction with the current bore (string) sample and
+    /// return the excitation to inject back into the delay line
+    pub fn excite(&mut self, bore_sample: f32) -> f32 {
+        let mouth_pressure = self.pressure;
+        let offset = self.offset;
+        let slope = self.slope;
+
+        self.oversampler.process(bore_sample, |bore| {
+            let dp = mouth_pressure * 0.5 - bore;
+            let reflection = (offset + slope * dp).clamp(-1.0, 1.0);
+            dp * reflection + mouth_pressure * 0.5
+        })
+    }
+
+    /// Reset oversampler filter state (does not affect `pressure`)
+    pub fn reset(&mut self) {
+        self.oversampler.reset();
+    }
+}
+
+impl Default for ReedModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bow-string friction junction with a two-tap bow-position pickup
+///
+/// Models bow/string interaction with the classic inverse-power friction
+/// curve. The relative bow-string velocity is approximated from two
+/// delay-line taps around the bow position, the same way a physical bow
+/// reads string velocity at a single contact point.
+pub struct BowModel {
+    /// Bow speed relative to the string, set by the player/controller
+    pub velocity: f32,
+    /// Bow force/pressure; scales how strongly friction couples energy in
+    pub force: f32,
+    /// Bow contact position along the string (0-1)
+    pub position: f32,
+    oversampler: Oversampler,
+}
+
+impl BowModel {
+    /// Create a new bow model at rest (silent until `velocity`/`force`
+    /// are raised)
+    pub fn new() -> Self {
+        BowModel {
+            velocity: 0.0,
+            force: 0.0,
+            position: 0.15,
+            oversampler: Oversampler::new(2),
+        }
+    }
+
+    /// Drive the junction from the two string samples taken at the bow
+    /// contact point and return the excitation to inject into the delay
+    /// line
+    pub fn excite(&mut self, tap_a: f32, tap_b: f32) -> f32 {
+        let bow_velocity = self.velocity;
+        let force = self.force;
+        let string_velocity = tap_a - tap_b;
+
+        self.oversampler.process(string_velocity, |string_velocity| {
+            let v = bow_velocity - string_velocity;
+            let reflection = (v.abs() + 0.75).powf(-4.0);
+            v * reflection * force
+        })
+    }
+
+    /// Reset oversampler filter state (does not affect `velocity`/`force`)
+    pub fn reset(&mut self) {
+        self.oversampler.reset();
+    }
+}
+
+impl Default for BowModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which excitation is currently driving a `KarplusStrong` string
+pub enum ExcitationMode {
+    /// One-shot plucked excitation (the original Karplus-Strong behavior)
+    Pluck,
+    /// Continuously driven single-reed (blown) excitation
+    Reed(ReedModel),
+    /// Continuously driven bowed excitation
+    Bow(BowModel),
+}
+
+impl Default for ExcitationMode {
+    fn default() -> Self {
+        ExcitationMode::Pluck
+    }
+}
+
+/// Selects which generator feeds a string's one-shot pluck excitation:
+/// plain white noise, or one of a few classic chaotic maps (named after
+/// their SuperCollider UGen counterparts) that give plucks a grainier,
+/// less "digital" character
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExcitationSource {
+    White,
+    Logistic,
+    Henon,
+    Lorenz,
+    Latoocarfian,
+}
+
+impl Default for ExcitationSource {
+    fn default() -> Self {
+        ExcitationSource::White
+    }
+}
+
+/// Stateful sample generator for `ExcitationSource`
+pub struct ChaosGenerator {
+    source: ExcitationSource,
+    noise_state: u32,
+    logistic_x: f32,
+    henon_x: f32,
+    henon_y: f32,
+    lorenz_x: f32,
+    lorenz_y: f32,
+    lorenz_z: f32,
+    latoocarfian_x: f32,
+    latoocarfian_y: f32,
+}
+
+impl ChaosGenerator {
+    /// Create a new generator, defaulting to white noise, with every
+    /// map seeded at a stable initial condition
+    pub fn new() -> Self {
+        ChaosGenerator {
+            source: ExcitationSource::White,
+            noise_state: 22222,
+            logistic_x: 0.51,
+            henon_x: 0.1,
+            henon_y: 0.1,
+            lorenz_x: 0.1,
+            lorenz_y: 0.0,
+            lorenz_z: 0.0,
+            latoocarfian_x: 0.5,
+            latoocarfian_y: 0.5,
+        }
+    }
+
+    /// Switch the active source (does not reset generator state, so
+    /// switching back later resumes where it left off)
+    pub fn set_source(&mut self, source: ExcitationSource) {
+        self.source = source;
+    }
+
+    /// Produce the next excitation sample in `[-1, 1]` from the active
+    /// source
+    #[inline]
+    pub fn next(&mut self) -> f32 {
+        match self.source {
+            ExcitationSource::White => {
+                // Linear Congruential Generator
+                self.noise_state = self.noise_state.wrapping_mul(1103515245).wrapping_add(12345);
+                (self.noise_state as f32 / u32::MAX as f32) * 2.0 - 1.0
+            }
+            ExcitationSource::Logistic => {
+                // Logistic map, r in the chaotic regime
+                const R: f32 = 3.97;
+                self.logistic_x = (R * self.logistic_x * (1.0 - self.logistic_x)).clamp(0.0, 1.0);
+                self.logistic_x * 2.0 - 1.0
+            }
+            ExcitationSource::Henon => {
+                // Henon map, classic parameters
+                const A: f32 = 1.4;
+                const B: f32 = 0.3;
+                let next_x = 1.0 - A * self.henon_x * self.henon_x + self.henon_y;
+                let next_y = B * self.henon_x;
+                self.henon_x = next_x.clamp(-1.5, 1.5);
+                self.henon_y = next_y;
+                self.henon_x / 1.5
+            }
+            ExcitationSource::Lorenz => {
+                // Lorenz system, Euler-integrated with a small step
+                const SIGMA: f32 = 10.0;
+                const RHO: f32 = 28.0;
+                const BETA: f32 = 8.0 / 3.0;
+                const DT: f32 = 0.01;
+                let dx = SIGMA * (self.lorenz_y - self.lorenz_x);
+                let dy = self.lorenz_x * (RHO - self.lorenz_z) - self.lorenz_y;
+                let dz = self.lorenz_x * self.lorenz_y - BETA * self.lorenz_z;
+                self.lorenz_x += dx * DT;
+                self.lorenz_y += dy * DT;
+                self.lorenz_z += dz * DT;
+                (self.lorenz_y / 20.0).clamp(-1.0, 1.0)
+            }
+            ExcitationSource::Latoocarfian => {
+                // Latoocarfian map, classic parameters
+                const A: f32 = 1.0;
+                const B: f32 = 3.0;
+                const C: f32 = 0.5;
+                const D: f32 = 0.5;
+                let next_x = (self.latoocarfian_y * B).sin() + C * (self.latoocarfian_x * B).sin();
+                let next_y = (self.latoocarfian_x * A).sin() + D * (self.latoocarfian_y * A).sin();
+                self.latoocarfian_x = next_x.clamp(-1.0, 1.0);
+                self.latoocarfian_y = next_y.clamp(-1.0, 1.0);
+                self.latoocarfian_x
+            }
+        }
+    }
+}
+
+impl Default for ChaosGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,6 +4,13 @@
 //! - OnePole: Simple first-order lowpass/highpass filter
 //! - Allpass: First-order allpass for fractional delay
 //! - Comb: Comb filter for resonance effects
+//! - StateVariable: Zero-delay-feedback (TPT) state-variable filter
+//! - Biquad: RBJ cookbook + Butterworth second-order sections
+//! - BiquadCascade: series chain of Biquad sections
+//! - DelayBuffer: fractional-delay ring buffer (linear/cubic/allpass)
+//! - fast_cos/fast_sin: wavetable-interpolated sine/cosine
+
+use std::sync::OnceLock;
 
 /// One-pole filter (first-order IIR)
 ///
@@ -155,16 +162,126 @@ impl Allpass {
     }
 }
 
+/// Interpolation mode used by `DelayBuffer::read_frac`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Straight-line interpolation between the two surrounding samples.
+    /// Cheapest, but dulls bright material by attenuating high frequencies.
+    Linear,
+    /// 4-point cubic Hermite (Catmull-Rom) interpolation. Keeps high
+    /// frequencies intact at the cost of 3 extra taps per sample.
+    Cubic,
+    /// First-order allpass interpolation (reuses `Allpass`). Exact tuning
+    /// with no amplitude ripple, at the cost of a slight phase smear that
+    /// settles in after a delay-length change.
+    Allpass,
+}
+
+/// A circular buffer with fractional-sample read access
+///
+/// Unifies the integer delay lines `Comb` used internally with the
+/// separate first-order `Allpass` used for tuning, so a single type can be
+/// smoothly modulated sample-by-sample for pitch bends, glissando, and
+/// vibrato without clicks.
+pub struct DelayBuffer {
+    buffer: Vec<f32>,
+    write_pos: usize,
+    mode: Interpolation,
+    /// Allpass filter state, used only in `Interpolation::Allpass` mode
+    allpass: Allpass,
+}
+
+impl DelayBuffer {
+    /// Create a new delay buffer that can hold up to `max_delay` samples
+    /// of history (plus a small guard region for the interpolation taps)
+    pub fn new(max_delay: usize, mode: Interpolation) -> Self {
+        DelayBuffer {
+            buffer: vec![0.0; max_delay.max(1) + 4],
+            write_pos: 0,
+            mode,
+            allpass: Allpass::new(0.0),
+        }
+    }
+
+    /// Change the interpolation mode
+    pub fn set_mode(&mut self, mode: Interpolation) {
+        self.mode = mode;
+    }
+
+    /// Write one new sample into the buffer
+    #[inline]
+    pub fn write(&mut self, sample: f32) {
+        self.buffer[self.write_pos] = sample;
+        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+    }
+
+    /// Read the sample `age` samples behind the last sample written
+    /// (age 0 is the most recently written sample, i.e. no delay)
+    #[inline]
+    fn read_at(&self, age: usize) -> f32 {
+        let len = self.buffer.len();
+        let pos = (self.write_pos + len - 1 - (age % len)) % len;
+        self.buffer[pos]
+    }
+
+    /// Read a fractionally-delayed sample using the configured
+    /// interpolation mode. `delay` is in samples and may be modulated
+    /// freely from one call to the next.
+    pub fn read_frac(&mut self, delay: f32) -> f32 {
+        let delay = delay.max(0.0);
+        match self.mode {
+            Interpolation::Linear => {
+                let d0 = delay.floor();
+                let frac = delay - d0;
+                let s0 = self.read_at(d0 as usize);
+                let s1 = self.read_at(d0 as usize + 1);
+                s0 * (1.0 - frac) + s1 * frac
+            }
+            Interpolation::Cubic => {
+                let d0 = delay.floor();
+                let frac = delay - d0;
+                let d0 = d0 as usize;
+                let y_m1 = self.read_at(d0.saturating_sub(1));
+                let y0 = self.read_at(d0);
+                let y1 = self.read_at(d0 + 1);
+                let y2 = self.read_at(d0 + 2);
+
+                let a0 = -0.5 * y_m1 + 1.5 * y0 - 1.5 * y1 + 0.5 * y2;
+                let a1 = y_m1 - 2.5 * y0 + 2.0 * y1 - 0.5 * y2;
+                let a2 = -0.5 * y_m1 + 0.5 * y1;
+                let a3 = y0;
+
+                ((a0 * frac + a1) * frac + a2) * frac + a3
+            }
+            Interpolation::Allpass => {
+                let d0 = delay.floor();
+                let frac = delay - d0;
+                let sample = self.read_at(d0 as usize);
+                // Recompute the allpass coefficient from the fractional part
+                let coef = (1.0 - frac) / (1.0 + frac);
+                self.allpass.set_coefficient(coef);
+                self.allpass.process(sample)
+            }
+        }
+    }
+
+    /// Clear the buffer and any interpolation filter state
+    pub fn clear(&mut self) {
+        for sample in &mut self.buffer {
+            *sample = 0.0;
+        }
+        self.allpass.reset();
+    }
+}
+
 /// Comb filter for resonance effects
 ///
 /// y[n] = x[n] + g * y[n - delay]
 pub struct Comb {
-    /// Delay buffer
-    buffer: Vec<f32>,
-    /// Current write position
-    write_pos: usize,
-    /// Delay in samples
-    delay: usize,
+    /// Delay buffer (supports fractional delay for modulation)
+    delay_line: DelayBuffer,
+    /// Delay in samples (may be fractional)
+    delay: f32,
     /// Feedback gain
     feedback: f32,
     /// Damping filter in feedback
@@ -174,10 +291,16 @@ pub struct Comb {
 impl Comb {
     /// Create a new comb filter
     pub fn new(delay_samples: usize, feedback: f32, damping: f32) -> Self {
+        Self::with_headroom(delay_samples, feedback, damping, 0)
+    }
+
+    /// Create a new comb filter whose delay line has `headroom` extra
+    /// samples of capacity beyond `delay_samples`, for callers that plan
+    /// to modulate the delay above its base value via `set_delay_frac`
+    pub fn with_headroom(delay_samples: usize, feedback: f32, damping: f32, headroom: usize) -> Self {
         Comb {
-            buffer: vec![0.0; delay_samples.max(1)],
-            write_pos: 0,
-            delay: delay_samples,
+            delay_line: DelayBuffer::new(delay_samples.max(1) + headroom, Interpolation::Linear),
+            delay: delay_samples as f32,
             feedback,
             damping: OnePole::new(damping),
         }
@@ -188,17 +311,32 @@ impl Comb {
         self.feedback = feedback.clamp(0.0, 0.99);
     }
 
+    /// Set feedback gain without the usual sub-unity clamp
+    ///
+    /// Used by freeze/infinite-sustain modes, which need exactly 1.0
+    /// (lossless) feedback rather than the normal stability margin.
+    pub(crate) fn set_feedback_raw(&mut self, feedback: f32) {
+        self.feedback = feedback;
+    }
+
     /// Set damping amount
     pub fn set_damping(&mut self, damping: f32) {
         self.damping.set_coefficient(damping);
     }
 
+    /// Set a fractional delay length in samples, for modulated (chorused)
+    /// combs. At the original integer delay this is bit-identical to the
+    /// fixed-delay behavior.
+    pub fn set_delay_frac(&mut self, delay: f32) {
+        self.delay = delay;
+    }
+
     /// Process one sample
     #[inline]
     pub fn process(&mut self, input: f32) -> f32 {
         // Read from delay line
-        let read_pos = (self.write_pos + self.buffer.len() - self.delay) % self.buffer.len();
-        let delayed = self.buffer[read_pos];
+        // (`read_frac` age 0 is the newest sample, so a D-sample delay reads age D - 1)
+        let delayed = self.delay_line.read_frac(self.delay - 1.0);
 
         // Apply damping filter to feedback
         let filtered = self.damping.process(delayed);
@@ -207,17 +345,14 @@ impl Comb {
         let output = input + filtered * self.feedback;
 
         // Write to delay line
-        self.buffer[self.write_pos] = output;
-        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+        self.delay_line.write(output);
 
         output
     }
 
     /// Clear the buffer
     pub fn clear(&mut self) {
-        for sample in &mut self.buffer {
-            *sample = 0.0;
-        }
+        self.delay_line.clear();
         self.damping.reset();
     }
 }
@@ -288,6 +423,157 @@ impl Biquad {
         }
     }
 
+    /// Build a biquad from raw (unnormalized) coefficients, normalizing by
+    /// `a0` as every RBJ cookbook formula expects
+    fn from_raw(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Biquad {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Create a bandpass biquad with constant skirt gain (peak gain = Q)
+    pub fn bandpass_skirt(center_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * center_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let alpha = sin_omega / (2.0 * q);
+
+        Biquad::from_raw(
+            q * alpha,
+            0.0,
+            -q * alpha,
+            1.0 + alpha,
+            -2.0 * cos_omega,
+            1.0 - alpha,
+        )
+    }
+
+    /// Create a bandpass biquad with constant 0 dB peak gain
+    pub fn bandpass_peak(center_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * center_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let alpha = sin_omega / (2.0 * q);
+
+        Biquad::from_raw(alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos_omega, 1.0 - alpha)
+    }
+
+    /// Create a notch (band-reject) biquad
+    pub fn notch(center_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * center_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let alpha = sin_omega / (2.0 * q);
+
+        Biquad::from_raw(
+            1.0,
+            -2.0 * cos_omega,
+            1.0,
+            1.0 + alpha,
+            -2.0 * cos_omega,
+            1.0 - alpha,
+        )
+    }
+
+    /// Create an allpass biquad (flat magnitude, frequency-dependent phase)
+    pub fn allpass(center_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * center_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let alpha = sin_omega / (2.0 * q);
+
+        Biquad::from_raw(
+            1.0 - alpha,
+            -2.0 * cos_omega,
+            1.0 + alpha,
+            1.0 + alpha,
+            -2.0 * cos_omega,
+            1.0 - alpha,
+        )
+    }
+
+    /// Create a peaking EQ biquad boosting/cutting by `gain_db` around
+    /// `center_hz`
+    pub fn peaking_eq(center_hz: f32, q: f32, gain_db: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * center_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let alpha = sin_omega / (2.0 * q);
+        let a = 10.0_f32.powf(gain_db / 40.0);
+
+        Biquad::from_raw(
+            1.0 + alpha * a,
+            -2.0 * cos_omega,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos_omega,
+            1.0 - alpha / a,
+        )
+    }
+
+    /// Create a low-shelf biquad boosting/cutting by `gain_db` below
+    /// `corner_hz`. `slope` is the RBJ shelf slope parameter (1.0 is the
+    /// steepest shelf with no peak/dip in the passband).
+    pub fn low_shelf(corner_hz: f32, slope: f32, gain_db: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * corner_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let a = 10.0_f32.powf(gain_db / 40.0);
+        let alpha = sin_omega / 2.0 * ((a + 1.0 / a) * (1.0 / slope - 1.0) + 2.0).sqrt();
+        let sqrt_a_alpha2 = 2.0 * a.sqrt() * alpha;
+
+        Biquad::from_raw(
+            a * ((a + 1.0) - (a - 1.0) * cos_omega + sqrt_a_alpha2),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos_omega),
+            a * ((a + 1.0) - (a - 1.0) * cos_omega - sqrt_a_alpha2),
+            (a + 1.0) + (a - 1.0) * cos_omega + sqrt_a_alpha2,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos_omega),
+            (a + 1.0) + (a - 1.0) * cos_omega - sqrt_a_alpha2,
+        )
+    }
+
+    /// Create a high-shelf biquad boosting/cutting by `gain_db` above
+    /// `corner_hz`. `slope` is the RBJ shelf slope parameter (1.0 is the
+    /// steepest shelf with no peak/dip in the passband).
+    pub fn high_shelf(corner_hz: f32, slope: f32, gain_db: f32, sample_rate: f32) -> Self {
+        let omega = 2.0 * std::f32::consts::PI * corner_hz / sample_rate;
+        let (sin_omega, cos_omega) = (omega.sin(), omega.cos());
+        let a = 10.0_f32.powf(gain_db / 40.0);
+        let alpha = sin_omega / 2.0 * ((a + 1.0 / a) * (1.0 / slope - 1.0) + 2.0).sqrt();
+        let sqrt_a_alpha2 = 2.0 * a.sqrt() * alpha;
+
+        Biquad::from_raw(
+            a * ((a + 1.0) + (a - 1.0) * cos_omega + sqrt_a_alpha2),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_omega),
+            a * ((a + 1.0) + (a - 1.0) * cos_omega - sqrt_a_alpha2),
+            (a + 1.0) - (a - 1.0) * cos_omega + sqrt_a_alpha2,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos_omega),
+            (a + 1.0) - (a - 1.0) * cos_omega - sqrt_a_alpha2,
+        )
+    }
+
+    /// Create a maximally-flat (Butterworth) second-order lowpass
+    ///
+    /// Unlike `lowpass`, which is parameterized by Q, this is derived
+    /// directly from the bilinear-transformed Butterworth polynomial, so
+    /// cascading several gives a proper higher-order Butterworth response
+    /// (see `BiquadCascade`).
+    pub fn butterworth_lowpass(cutoff_hz: f32, sample_rate: f32) -> Self {
+        let f = (std::f32::consts::PI * cutoff_hz / sample_rate).tan();
+        let a0r = 1.0 / (1.0 + std::f32::consts::SQRT_2 * f + f * f);
+        let b0 = f * f * a0r;
+
+        Biquad {
+            b0,
+            b1: 2.0 * b0,
+            b2: b0,
+            a1: (2.0 * f * f - 2.0) * a0r,
+            a2: (1.0 - std::f32::consts::SQRT_2 * f + f * f) * a0r,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
     /// Process one sample (Direct Form II Transposed)
     #[inline]
     pub fn process(&mut self, input: f32) -> f32 {
@@ -303,3 +589,184 @@ impl Biquad {
         self.z2 = 0.0;
     }
 }
+
+/// A series cascade of `Biquad` sections
+///
+/// Cascading second-order sections is the standard way to build
+/// higher-order filters (steep body-resonance EQ, multi-pole lowpass loop
+/// filters) out of numerically well-behaved second-order building blocks.
+pub struct BiquadCascade {
+    sections: Vec<Biquad>,
+}
+
+impl BiquadCascade {
+    /// Create an empty cascade
+    pub fn new() -> Self {
+        BiquadCascade { sections: Vec::new() }
+    }
+
+    /// Create a cascade from an existing list of sections
+    pub fn from_sections(sections: Vec<Biquad>) -> Self {
+        BiquadCascade { sections }
+    }
+
+    /// Append a section to the end of the chain
+    pub fn push(&mut self, section: Biquad) {
+        self.sections.push(section);
+    }
+
+    /// Process one sample through every section in series
+    #[inline]
+    pub fn process(&mut self, input: f32) -> f32 {
+        let mut sample = input;
+        for section in &mut self.sections {
+            sample = section.process(sample);
+        }
+        sample
+    }
+
+    /// Reset all sections' filter state
+    pub fn reset(&mut self) {
+        for section in &mut self.sections {
+            section.reset();
+        }
+    }
+}
+
+impl Default for BiquadCascade {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Zero-delay-feedback state-variable filter (TPT topology)
+///
+/// Based on the trapezoidal-integrator SVF described by Vadim Zavalishin.
+/// Unlike `OnePole` and `Biquad`, this topology stays stable even when
+/// the cutoff is modulated every sample and pushed close to Nyquist,
+/// which matters for per-sample brightness control in the string
+/// feedback loop. All four responses are available from one `process`
+/// call, so callers don't pay for filters they aren't using.
+pub struct StateVariable {
+    /// Prewarped cutoff coefficient: tan(pi * cutoff_hz / sample_rate)
+    g: f32,
+    /// Resonance coefficient: 1 / q
+    k: f32,
+    a1: f32,
+    a2: f32,
+    a3: f32,
+    /// Integrator state (state variable 1)
+    ic1eq: f32,
+    /// Integrator state (state variable 2)
+    ic2eq: f32,
+    sample_rate: f32,
+}
+
+/// The four simultaneous outputs of a `StateVariable::process` call
+pub struct SvfOutputs {
+    pub lowpass: f32,
+    pub bandpass: f32,
+    pub highpass: f32,
+    pub notch: f32,
+}
+
+impl StateVariable {
+    /// Create a new state-variable filter
+    pub fn new(cutoff_hz: f32, q: f32, sample_rate: f32) -> Self {
+        let mut svf = StateVariable {
+            g: 0.0,
+            k: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            a3: 0.0,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+            sample_rate,
+        };
+        svf.k = 1.0 / q.max(0.01);
+        svf.set_cutoff(cutoff_hz);
+        svf
+    }
+
+    /// Recompute the g-derived coefficients for a new cutoff
+    ///
+    /// Cheap enough to call every sample for audio-rate sweeps without
+    /// zipper noise or blow-ups, since the topology itself is what stays
+    /// stable near Nyquist (not just this coefficient update).
+    pub fn set_cutoff(&mut self, cutoff_hz: f32) {
+        self.g = (std::f32::consts::PI * cutoff_hz / self.sample_rate).tan();
+        self.a1 = 1.0 / (1.0 + self.g * (self.g + self.k));
+        self.a2 = self.g * self.a1;
+        self.a3 = self.g * self.a2;
+    }
+
+    /// Set resonance (Q)
+    pub fn set_q(&mut self, q: f32) {
+        self.k = 1.0 / q.max(0.01);
+        // a1/a2/a3 depend on k too, so recompute from the current g
+        let a1 = 1.0 / (1.0 + self.g * (self.g + self.k));
+        self.a1 = a1;
+        self.a2 = self.g * a1;
+        self.a3 = self.g * self.a2;
+    }
+
+    /// Process one sample, returning all four responses at once
+    #[inline]
+    pub fn process(&mut self, input: f32) -> SvfOutputs {
+        let v3 = input - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        SvfOutputs {
+            lowpass: v2,
+            bandpass: v1,
+            highpass: input - self.k * v1 - v2,
+            notch: input - self.k * v1,
+        }
+    }
+
+    /// Reset filter state
+    pub fn reset(&mut self) {
+        self.ic1eq = 0.0;
+        self.ic2eq = 0.0;
+    }
+}
+
+/// Wavetable size for `fast_cos`/`fast_sin`; one extra guard sample lets
+/// the interpolation at the last index read `table[i + 1]` without
+/// wrapping arithmetic.
+const COS_TABLE_SIZE: usize = 512;
+const COS_TABLE_GUARD_SIZE: usize = COS_TABLE_SIZE + 1;
+
+static COS_TABLE: OnceLock<[f32; COS_TABLE_GUARD_SIZE]> = OnceLock::new();
+
+fn cos_table() -> &'static [f32; COS_TABLE_GUARD_SIZE] {
+    COS_TABLE.get_or_init(|| {
+        let mut table = [0.0f32; COS_TABLE_GUARD_SIZE];
+        for (i, slot) in table.iter_mut().enumerate() {
+            *slot = ((i as f32) * std::f32::consts::TAU / COS_TABLE_SIZE as f32).cos();
+        }
+        table
+    })
+}
+
+/// Wavetable-interpolated cosine, accurate to within ~1e-3 of `f32::cos`.
+/// Lazily builds its 513-entry lookup table on first call (thread-safe,
+/// built once); every call after that is a table lookup plus one lerp.
+pub fn fast_cos(x: f32) -> f32 {
+    let table = cos_table();
+
+    let phase = x * (COS_TABLE_SIZE as f32 / std::f32::consts::TAU);
+    let phase = phase.rem_euclid(COS_TABLE_SIZE as f32);
+    let index = phase as usize;
+    let frac = phase - index as f32;
+
+    table[index] + (table[index + 1] - table[index]) * frac
+}
+
+/// Wavetable-interpolated sine, implemented as `fast_cos(x - pi/2)`
+pub fn fast_sin(x: f32) -> f32 {
+    fast_cos(x - std::f32::consts::FRAC_PI_2)
+}
@@ -37,18 +37,44 @@ mod string;
 mod voice;
 mod resonance;
 mod reverb;
+mod plate_reverb;
 mod filters;
+mod oversample;
+mod excitation;
+mod pitch;
+mod sequencer;
+mod patch;
+mod phrase;
+mod ensemble;
+mod body_convolver;
+mod sympathetic_bank;
 
 use string::KarplusStrong;
-use voice::VoicePool;
+use voice::{NoteEvent, VoicePool};
 use resonance::SympatheticMatrix;
 use reverb::FDNReverb;
+use plate_reverb::DattorroReverb;
+use sequencer::{ScheduledAction, Sequencer};
+use patch::{Patch, StringPatch};
+use phrase::{Dynamic, PhraseAttribute, PhraseNote};
+use excitation::ExcitationSource;
+use pitch::{PitchCorrector, PitchMode};
+use ensemble::StringEnsemble;
+use sympathetic_bank::SympatheticBank;
+use body_convolver::BodyConvolver;
+use filters::{Biquad, BiquadCascade};
 
 // Constants
 pub const NUM_STRINGS: usize = 12;
 pub const MAX_VOICES: usize = 128;
 pub const SAMPLE_RATE: f32 = 44100.0;
 
+/// Default voice count for the standalone `StringEnsemble`
+const ENSEMBLE_VOICES: usize = 8;
+
+/// Partition size for `BodyConvolver`'s uniformly-partitioned FFT
+const BODY_CONVOLVER_BLOCK_SIZE: usize = 256;
+
 /// Pitch class names for display
 pub const PC_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
 
@@ -59,6 +85,44 @@ pub fn pc_to_freq(pitch_class: usize, octave: i32) -> f32 {
     440.0 * 2.0_f32.powf((midi_note as f32 - 69.0) / 12.0)
 }
 
+/// Convert a true MIDI note number to frequency in Hz
+#[inline]
+pub fn midi_to_freq(midi_note: u8) -> f32 {
+    440.0 * 2.0_f32.powf((midi_note as f32 - 69.0) / 12.0)
+}
+
+/// Map a numeric code onto an `ExcitationSource` (0=white, 1=logistic,
+/// 2=henon, 3=lorenz, 4=latoocarfian; anything else falls back to white)
+#[inline]
+fn excitation_source_from_code(code: u8) -> ExcitationSource {
+    match code {
+        1 => ExcitationSource::Logistic,
+        2 => ExcitationSource::Henon,
+        3 => ExcitationSource::Lorenz,
+        4 => ExcitationSource::Latoocarfian,
+        _ => ExcitationSource::White,
+    }
+}
+
+/// Build a single-section output EQ biquad from a type code (0=lowpass,
+/// 1=highpass, 2=peaking, 3=low shelf, 4=high shelf, 5=Butterworth
+/// lowpass), `freq`/`q` as in the RBJ cookbook, and `gain_db` for the
+/// shelf/peaking shapes (ignored otherwise)
+fn output_eq_biquad_from_code(code: u8, freq: f32, q: f32, gain_db: f32, sample_rate: f32) -> Biquad {
+    match code {
+        1 => Biquad::highpass(freq, q, sample_rate),
+        2 => Biquad::peaking_eq(freq, q, gain_db, sample_rate),
+        3 => Biquad::low_shelf(freq, q, gain_db, sample_rate),
+        4 => Biquad::high_shelf(freq, q, gain_db, sample_rate),
+        5 => Biquad::butterworth_lowpass(freq, sample_rate),
+        _ => Biquad::lowpass(freq, q, sample_rate),
+    }
+}
+
+/// Damping applied to a melodic voice's string once it enters release,
+/// so held notes decay promptly instead of only on voice-steal
+const MELODIC_RELEASE_DAMPING: f32 = 0.95;
+
 /// Main synthesizer engine
 #[wasm_bindgen]
 pub struct Sympathetic12 {
@@ -74,6 +138,14 @@ pub struct Sympathetic12 {
     /// Reverb processor
     reverb: FDNReverb,
 
+    /// Alternative modulated plate (Dattorro) reverb, used instead of
+    /// `reverb` when `use_plate_reverb` is set
+    plate_reverb: DattorroReverb,
+
+    /// When true, `process` routes through `plate_reverb` instead of
+    /// the default `reverb`
+    use_plate_reverb: bool,
+
     /// Master volume (0-1)
     master_volume: f32,
 
@@ -95,6 +167,53 @@ pub struct Sympathetic12 {
 
     /// String energy levels (for visualization)
     string_energies: Vec<f32>,
+
+    /// Per-voice strings for true MIDI-note polyphony (indexed by voice
+    /// ID from `voice_pool`; `None` where the slot is unused). Unlike
+    /// the 12 fixed resonator `strings`, these are retuned per note_on
+    /// to the note's actual frequency rather than clamped to a pitch
+    /// class in `base_octave`.
+    melodic_strings: Vec<Option<KarplusStrong>>,
+
+    /// Internal tempo-clocked event scheduler, driving `pluck`/
+    /// `pluck_prime_form` in time so a host only has to call `process`
+    sequencer: Sequencer,
+
+    /// Autotune-style pitch follower driving strings from monophonic
+    /// input, via `process_monophonic_input`
+    pitch_corrector: PitchCorrector,
+
+    /// Standalone polyphonic ensemble, frequency-addressed and mixed
+    /// alongside the 12 fixed pitch-class strings
+    ensemble: StringEnsemble,
+
+    /// Ensemble wet level mixed into the main output (0-1)
+    ensemble_mix: f32,
+
+    /// Scratch buffer the ensemble renders into each block before mixing
+    ensemble_buffer: Vec<f32>,
+
+    /// Flat bank of extra resonators coupled sympathetically to each
+    /// other (separate from the fixed-12 `sympathy` matrix); empty
+    /// until a host calls `add_sympathetic_resonator`
+    sympathetic_bank: SympatheticBank,
+
+    /// Sympathetic bank wet level mixed into the main output (0-1)
+    sympathetic_bank_mix: f32,
+
+    /// Convolution-based instrument body resonance, applied to the
+    /// summed string signal; bypassed until a host loads an IR via
+    /// `set_body_ir`
+    body_convolver: BodyConvolver,
+
+    /// Output EQ cascade applied to the final stereo mix (left/right
+    /// share coefficients but keep independent filter state); empty by
+    /// default so it's a no-op until a host calls `set_output_eq`
+    output_eq_left: BiquadCascade,
+    output_eq_right: BiquadCascade,
+
+    /// When true, `process` skips the output EQ cascade
+    output_eq_bypass: bool,
 }
 
 #[wasm_bindgen]
@@ -116,11 +235,18 @@ impl Sympathetic12 {
             })
             .collect();
 
+        // Bypassed by default: silent dry/wet change until a host loads
+        // an IR and explicitly un-bypasses it
+        let mut body_convolver = BodyConvolver::new(BODY_CONVOLVER_BLOCK_SIZE);
+        body_convolver.set_bypass(true);
+
         Sympathetic12 {
             strings,
             voice_pool: VoicePool::new(MAX_VOICES),
             sympathy: SympatheticMatrix::new(),
             reverb: FDNReverb::new(SAMPLE_RATE),
+            plate_reverb: DattorroReverb::new(SAMPLE_RATE),
+            use_plate_reverb: false,
             master_volume: 0.7,
             reverb_mix: 0.25,
             base_octave,
@@ -129,9 +255,99 @@ impl Sympathetic12 {
             output_right: vec![0.0; 128],
             string_outputs: vec![0.0; NUM_STRINGS],
             string_energies: vec![0.0; NUM_STRINGS],
+            melodic_strings: (0..MAX_VOICES).map(|_| None).collect(),
+            sequencer: Sequencer::new(SAMPLE_RATE),
+            pitch_corrector: PitchCorrector::new(SAMPLE_RATE),
+            ensemble: StringEnsemble::new(ENSEMBLE_VOICES),
+            ensemble_mix: 0.0,
+            ensemble_buffer: vec![0.0; 128],
+            sympathetic_bank: SympatheticBank::new(),
+            sympathetic_bank_mix: 0.0,
+            body_convolver,
+            output_eq_left: BiquadCascade::new(),
+            output_eq_right: BiquadCascade::new(),
+            output_eq_bypass: true,
+        }
+    }
+
+    /// Set the internal sequencer's tempo (beats per minute)
+    #[wasm_bindgen]
+    pub fn set_tempo(&mut self, bpm: f32) {
+        self.sequencer.set_tempo(bpm);
+    }
+
+    /// Schedule a single-string pluck at `beat` beats from the loop start
+    #[wasm_bindgen]
+    pub fn schedule_pluck(&mut self, beat: f64, pitch_class: usize, velocity: f32, position: f32) {
+        self.sequencer.schedule_pluck(beat, pitch_class, velocity, position);
+    }
+
+    /// Schedule a Forte prime-form chord at `beat` beats from the loop start
+    #[wasm_bindgen]
+    pub fn schedule_prime_form(&mut self, beat: f64, prime_form: &[u8], transposition: u8, velocity: f32) {
+        self.sequencer.schedule_prime_form(beat, prime_form.to_vec(), transposition, velocity);
+    }
+
+    /// Clear all scheduled events and reset the sequencer's sample counter
+    #[wasm_bindgen]
+    pub fn clear_schedule(&mut self) {
+        self.sequencer.clear_schedule();
+    }
+
+    /// Set the sequencer's loop length in beats (0 disables looping)
+    #[wasm_bindgen]
+    pub fn set_loop_length_beats(&mut self, beats: f64) {
+        self.sequencer.set_loop_length_beats(beats);
+    }
+
+    /// Trigger a melodic voice at the given true MIDI note (spans
+    /// octaves, unlike `pluck`'s fixed 12 pitch-class strings)
+    #[wasm_bindgen]
+    pub fn note_on(&mut self, midi_note: u8, velocity: f32) {
+        let pitch_class = (midi_note as usize) % NUM_STRINGS;
+
+        if let Some(voice_id) = self.voice_pool.allocate(pitch_class) {
+            self.voice_pool.set_active(voice_id, pitch_class, velocity);
+            self.voice_pool.set_note(voice_id, midi_note);
+
+            let freq = midi_to_freq(midi_note);
+            let mut voice_string = KarplusStrong::new(freq, SAMPLE_RATE);
+            voice_string.pluck(velocity, 0.5);
+            self.melodic_strings[voice_id] = Some(voice_string);
+        }
+    }
+
+    /// Release the melodic voice(s) currently playing `midi_note`
+    ///
+    /// Moves matching voices into their envelope's release segment and
+    /// raises their string's damping so they decay promptly; the slot
+    /// is recycled once the envelope finishes.
+    #[wasm_bindgen]
+    pub fn note_off(&mut self, midi_note: u8) {
+        for voice_id in self.voice_pool.active_voice_ids() {
+            if self.voice_pool.get_voice(voice_id).map(|v| v.note) == Some(midi_note) {
+                self.voice_pool.release(voice_id);
+                if let Some(voice_string) = &mut self.melodic_strings[voice_id] {
+                    voice_string.set_damping(MELODIC_RELEASE_DAMPING);
+                }
+            }
         }
     }
 
+    /// Schedule a melodic voice to trigger `sample_offset` samples into
+    /// the *next* `process` call, instead of immediately like `note_on`
+    #[wasm_bindgen]
+    pub fn schedule_note_on(&mut self, sample_offset: u32, midi_note: u8, velocity: f32) {
+        self.voice_pool.push_next(sample_offset, NoteEvent::NoteOn { midi_note, velocity });
+    }
+
+    /// Schedule a melodic voice release `sample_offset` samples into the
+    /// *next* `process` call, instead of immediately like `note_off`
+    #[wasm_bindgen]
+    pub fn schedule_note_off(&mut self, sample_offset: u32, midi_note: u8) {
+        self.voice_pool.push_next(sample_offset, NoteEvent::NoteOff { midi_note });
+    }
+
     /// Pluck a string (pitch_class 0-11, velocity 0-1, position 0-1)
     #[wasm_bindgen]
     pub fn pluck(&mut self, pitch_class: usize, velocity: f32, position: f32) {
@@ -166,6 +382,231 @@ impl Sympathetic12 {
         }
     }
 
+    /// Set the pitch corrector's mode (0=snap to detected pitch,
+    /// 1=follow the manually supplied note)
+    #[wasm_bindgen]
+    pub fn set_pitch_mode(&mut self, mode: u8) {
+        self.pitch_corrector.set_mode(if mode == 1 { PitchMode::Manual } else { PitchMode::Snap });
+    }
+
+    /// Set the pitch corrector's frequency multiplier, applied before
+    /// snapping to the nearest string (e.g. 2.0 shifts up an octave)
+    #[wasm_bindgen]
+    pub fn set_pitch_frequency_gain(&mut self, gain: f32) {
+        self.pitch_corrector.set_frequency_gain(gain);
+    }
+
+    /// Supply the MIDI note the pitch corrector follows in manual mode
+    #[wasm_bindgen]
+    pub fn set_pitch_manual_note(&mut self, midi_note: u8) {
+        self.pitch_corrector.set_manual_note(midi_note);
+    }
+
+    /// Analyze a buffer of monophonic input (e.g. a live mic feed) and
+    /// pluck whichever string the pitch corrector chooses, scaling
+    /// velocity by detection confidence
+    #[wasm_bindgen]
+    pub fn process_monophonic_input(&mut self, buffer: &[f32], velocity: f32, position: f32) {
+        if let Some(result) = self.pitch_corrector.process(buffer) {
+            self.pluck(result.string_index, velocity * result.confidence, position);
+        }
+    }
+
+    /// Trigger a voice in the standalone ensemble at `freq` Hz
+    #[wasm_bindgen]
+    pub fn ensemble_note_on(&mut self, freq: f32, velocity: f32, position: f32) {
+        self.ensemble.note_on(freq, velocity, position);
+    }
+
+    /// Trigger several ensemble voices at once (a chord)
+    #[wasm_bindgen]
+    pub fn ensemble_note_on_chord(&mut self, freqs: &[f32], velocity: f32, position: f32) {
+        self.ensemble.note_on_chord(freqs, velocity, position);
+    }
+
+    /// Release every ensemble voice currently sounding `freq`
+    #[wasm_bindgen]
+    pub fn ensemble_note_off(&mut self, freq: f32) {
+        self.ensemble.note_off(freq);
+    }
+
+    /// Set the ensemble's ADSR attack time in milliseconds
+    #[wasm_bindgen]
+    pub fn set_ensemble_attack_ms(&mut self, ms: f32) {
+        self.ensemble.set_attack_ms(ms);
+    }
+
+    /// Set the ensemble's ADSR decay time in milliseconds
+    #[wasm_bindgen]
+    pub fn set_ensemble_decay_ms(&mut self, ms: f32) {
+        self.ensemble.set_decay_ms(ms);
+    }
+
+    /// Set the ensemble's ADSR sustain level (0-1)
+    #[wasm_bindgen]
+    pub fn set_ensemble_sustain(&mut self, level: f32) {
+        self.ensemble.set_sustain(level);
+    }
+
+    /// Set the ensemble's ADSR release time in milliseconds
+    #[wasm_bindgen]
+    pub fn set_ensemble_release_ms(&mut self, ms: f32) {
+        self.ensemble.set_release_ms(ms);
+    }
+
+    /// Set how much of the standalone ensemble is mixed into the main
+    /// output (0-1, default 0 = silent)
+    #[wasm_bindgen]
+    pub fn set_ensemble_mix(&mut self, mix: f32) {
+        self.ensemble_mix = mix.clamp(0.0, 1.0);
+    }
+
+    /// Add a resonator to the sympathetic bank tuned to `freq` Hz,
+    /// returning its index for later `pluck_sympathetic_resonator` calls
+    #[wasm_bindgen]
+    pub fn add_sympathetic_resonator(&mut self, freq: f32) -> usize {
+        self.sympathetic_bank.add_resonator(freq)
+    }
+
+    /// Directly pluck a sympathetic bank resonator by index
+    #[wasm_bindgen]
+    pub fn pluck_sympathetic_resonator(&mut self, index: usize, velocity: f32, position: f32) {
+        self.sympathetic_bank.pluck(index, velocity, position);
+    }
+
+    /// Set the sympathetic bank's overall coupling gain between resonators
+    #[wasm_bindgen]
+    pub fn set_sympathetic_bank_coupling(&mut self, gain: f32) {
+        self.sympathetic_bank.set_coupling(gain);
+    }
+
+    /// Set how much of the sympathetic bank is mixed into the main
+    /// output (0-1, default 0 = silent)
+    #[wasm_bindgen]
+    pub fn set_sympathetic_bank_mix(&mut self, mix: f32) {
+        self.sympathetic_bank_mix = mix.clamp(0.0, 1.0);
+    }
+
+    /// Load a new body impulse response for `BodyConvolver`, replacing
+    /// any previously loaded one
+    #[wasm_bindgen]
+    pub fn set_body_ir(&mut self, ir: &[f32]) {
+        self.body_convolver.set_body_ir(ir);
+    }
+
+    /// Set the body convolver's dry/wet mix (0-1)
+    #[wasm_bindgen]
+    pub fn set_body_mix(&mut self, mix: f32) {
+        self.body_convolver.set_mix(mix);
+    }
+
+    /// Enable/disable the body convolver (bypassed by default)
+    #[wasm_bindgen]
+    pub fn set_body_bypass(&mut self, bypass: bool) {
+        self.body_convolver.set_bypass(bypass);
+    }
+
+    /// Replace the output EQ with a single section built from a type
+    /// code (0=lowpass, 1=highpass, 2=peaking, 3=low shelf, 4=high
+    /// shelf, 5=Butterworth lowpass), un-bypassing it in the process
+    #[wasm_bindgen]
+    pub fn set_output_eq(&mut self, filter_type: u8, freq: f32, q: f32, gain_db: f32) {
+        let left = output_eq_biquad_from_code(filter_type, freq, q, gain_db, SAMPLE_RATE);
+        let right = output_eq_biquad_from_code(filter_type, freq, q, gain_db, SAMPLE_RATE);
+        self.output_eq_left = BiquadCascade::from_sections(vec![left]);
+        self.output_eq_right = BiquadCascade::from_sections(vec![right]);
+        self.output_eq_bypass = false;
+    }
+
+    /// Append another section to the output EQ cascade (e.g. to build a
+    /// steeper multi-pole response), un-bypassing it in the process
+    #[wasm_bindgen]
+    pub fn add_output_eq_section(&mut self, filter_type: u8, freq: f32, q: f32, gain_db: f32) {
+        self.output_eq_left.push(output_eq_biquad_from_code(filter_type, freq, q, gain_db, SAMPLE_RATE));
+        self.output_eq_right.push(output_eq_biquad_from_code(filter_type, freq, q, gain_db, SAMPLE_RATE));
+        self.output_eq_bypass = false;
+    }
+
+    /// Clear the output EQ cascade back to empty and bypass it
+    #[wasm_bindgen]
+    pub fn clear_output_eq(&mut self) {
+        self.output_eq_left = BiquadCascade::new();
+        self.output_eq_right = BiquadCascade::new();
+        self.output_eq_bypass = true;
+    }
+
+    /// Enable/disable the output EQ cascade without clearing it
+    #[wasm_bindgen]
+    pub fn set_output_eq_bypass(&mut self, bypass: bool) {
+        self.output_eq_bypass = bypass;
+    }
+
+    /// Render a musical phrase: parallel arrays describe each note
+    /// (`pitch_classes`, `octaves`, `start_beats`, `duration_beats`,
+    /// `dynamics` as 0=pp..5=ff), shaped by `staccato_factor` (<= 0
+    /// disables it), `accent` (boosts the first note), a crescendo from
+    /// `crescendo_from` to `crescendo_to` (dynamic codes; pass a
+    /// negative `crescendo_from` to disable), and `legato` (lets notes
+    /// ring into each other instead of damping at the end of each
+    /// note's duration). Compiles the phrase into scheduled pluck/damp
+    /// events on the internal sequencer and sets
+    /// `set_global_brightness` to the phrase's average velocity.
+    #[wasm_bindgen]
+    // Parallel-array wasm signature, same convention as `pluck_set`/
+    // `set_sympathy_matrix`; flattening further would fight the binding
+    // rather than simplify it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_phrase(
+        &mut self,
+        pitch_classes: &[u8],
+        octaves: &[i32],
+        start_beats: &[f64],
+        duration_beats: &[f64],
+        dynamics: &[u8],
+        staccato_factor: f32,
+        accent: bool,
+        crescendo_from: i32,
+        crescendo_to: i32,
+        legato: bool,
+    ) {
+        let count = pitch_classes
+            .len()
+            .min(octaves.len())
+            .min(start_beats.len())
+            .min(duration_beats.len())
+            .min(dynamics.len());
+
+        let notes: Vec<PhraseNote> = (0..count)
+            .map(|i| PhraseNote {
+                pitch_class: pitch_classes[i] as usize,
+                octave: octaves[i],
+                start_beat: start_beats[i],
+                duration_beats: duration_beats[i],
+                dynamic: Dynamic::from_code(dynamics[i]),
+            })
+            .collect();
+
+        let mut attributes = Vec::new();
+        if staccato_factor > 0.0 {
+            attributes.push(PhraseAttribute::Staccato(staccato_factor));
+        }
+        if accent {
+            attributes.push(PhraseAttribute::Accent);
+        }
+        if crescendo_from >= 0 && crescendo_to >= 0 {
+            attributes.push(PhraseAttribute::Crescendo(
+                Dynamic::from_code(crescendo_from as u8),
+                Dynamic::from_code(crescendo_to as u8),
+            ));
+        }
+        if legato {
+            attributes.push(PhraseAttribute::Legato);
+        }
+
+        let avg_velocity = phrase::render_phrase(&mut self.sequencer, &notes, &attributes);
+        self.set_global_brightness(avg_velocity);
+    }
+
     /// Damp a specific string
     #[wasm_bindgen]
     pub fn damp(&mut self, pitch_class: usize, amount: f32) {
@@ -198,8 +639,66 @@ impl Sympathetic12 {
             self.output_right[i] = 0.0;
         }
 
+        // Render the standalone ensemble a block at a time (its own API
+        // is block-based, unlike the per-sample fixed-12 strings)
+        if self.ensemble_buffer.len() < num_samples {
+            self.ensemble_buffer.resize(num_samples, 0.0);
+        }
+        self.ensemble.process(&mut self.ensemble_buffer[..num_samples]);
+
+        // Drain any events scheduled (via `schedule_note_on`/`schedule_note_off`)
+        // for this block up front: this both ticks voice ages sample-accurately
+        // between events and tells us the offset each landed on, so the
+        // resulting voice's audio below starts exactly there instead of at
+        // the top of the block.
+        let mut pending_note_events: Vec<(u32, NoteEvent, Option<usize>)> = Vec::new();
+        self.voice_pool.process_block(num_samples as u32, |offset, event, voice_id| {
+            pending_note_events.push((offset, *event, voice_id));
+        });
+        let mut pending_index = 0;
+
         // Process each sample
         for i in 0..num_samples {
+            // Start/stop voices for events scheduled at this exact sample
+            while pending_index < pending_note_events.len()
+                && pending_note_events[pending_index].0 == i as u32
+            {
+                let (_, event, voice_id) = pending_note_events[pending_index];
+                if let Some(voice_id) = voice_id {
+                    match event {
+                        NoteEvent::NoteOn { midi_note, velocity } => {
+                            let freq = midi_to_freq(midi_note);
+                            let mut voice_string = KarplusStrong::new(freq, SAMPLE_RATE);
+                            voice_string.pluck(velocity, 0.5);
+                            self.melodic_strings[voice_id] = Some(voice_string);
+                        }
+                        NoteEvent::NoteOff { .. } => {
+                            if let Some(voice_string) = &mut self.melodic_strings[voice_id] {
+                                voice_string.set_damping(MELODIC_RELEASE_DAMPING);
+                            }
+                        }
+                    }
+                }
+                pending_index += 1;
+            }
+
+            // Fire any scheduled events due at this sample before running
+            // the string/reverb stages, so a host only needs to call
+            // `process` in a loop for the sequencer to play itself
+            for action in self.sequencer.tick() {
+                match action {
+                    ScheduledAction::Pluck { pitch_class, velocity, position } => {
+                        self.pluck(pitch_class, velocity, position);
+                    }
+                    ScheduledAction::PrimeForm { prime_form, transposition, velocity } => {
+                        self.pluck_prime_form(&prime_form, transposition, velocity);
+                    }
+                    ScheduledAction::Damp { pitch_class, amount } => {
+                        self.damp(pitch_class, amount);
+                    }
+                }
+            }
+
             // Get sympathetic excitation based on previous string outputs and energies
             let excitation = self.sympathy.process(&self.string_outputs, &self.string_energies, self.sympathy_amount);
 
@@ -213,6 +712,30 @@ impl Sympathetic12 {
                 }
             }
 
+            // Mix in melodic (true MIDI-note) voices: each one rings at
+            // its own octave but still couples into the sympathetic
+            // matrix through its note's pitch class
+            for voice_id in self.voice_pool.active_voice_ids() {
+                let Some(voice_string) = &mut self.melodic_strings[voice_id] else { continue };
+                let raw_sample = voice_string.process(0.0);
+                let energy = voice_string.get_energy();
+
+                if let Some(voice) = self.voice_pool.get_voice_mut(voice_id) {
+                    let env_level = voice.envelope.advance();
+                    let sample = raw_sample * env_level;
+                    let pitch_class = (voice.note as usize) % NUM_STRINGS;
+                    if sample.is_finite() {
+                        self.string_outputs[pitch_class] += sample;
+                    }
+                    self.string_energies[pitch_class] = self.string_energies[pitch_class].max(energy * env_level);
+
+                    if voice.releasing && voice.envelope.is_finished() {
+                        self.voice_pool.deactivate(voice_id);
+                        self.melodic_strings[voice_id] = None;
+                    }
+                }
+            }
+
             // Mix strings to stereo and compute mono for reverb
             let mut mono = 0.0f32;
             for s in 0..NUM_STRINGS {
@@ -226,9 +749,41 @@ impl Sympathetic12 {
             }
             mono /= NUM_STRINGS as f32;
 
+            // Route the summed string signal through convolution-based
+            // body modeling. The convolver's own dry/wet mix already
+            // accounts for the unconvolved portion, so swap its output in
+            // for the raw centered sum rather than adding on top of it
+            // (a no-op delta while bypassed, since `process` then returns
+            // its input unchanged).
+            let body_sample = self.body_convolver.process(mono);
+            let body_delta = body_sample - mono;
+            self.output_left[i] += body_delta;
+            self.output_right[i] += body_delta;
+            mono += body_delta;
+
+            // Mix in the standalone ensemble, centered
+            if self.ensemble_mix > 0.001 {
+                let ensemble_sample = self.ensemble_buffer[i] * self.ensemble_mix;
+                self.output_left[i] += ensemble_sample;
+                self.output_right[i] += ensemble_sample;
+                mono += ensemble_sample;
+            }
+
+            // Mix in the sympathetic resonator bank, centered
+            if self.sympathetic_bank_mix > 0.001 && !self.sympathetic_bank.is_empty() {
+                let bank_sample = self.sympathetic_bank.process() * self.sympathetic_bank_mix;
+                self.output_left[i] += bank_sample;
+                self.output_right[i] += bank_sample;
+                mono += bank_sample;
+            }
+
             // Apply reverb
             if self.reverb_mix > 0.001 {
-                let (rev_l, rev_r) = self.reverb.process(mono);
+                let (rev_l, rev_r) = if self.use_plate_reverb {
+                    self.plate_reverb.process(mono)
+                } else {
+                    self.reverb.process(mono)
+                };
                 self.output_left[i] += rev_l * self.reverb_mix;
                 self.output_right[i] += rev_r * self.reverb_mix;
             }
@@ -237,6 +792,12 @@ impl Sympathetic12 {
             self.output_left[i] *= self.master_volume;
             self.output_right[i] *= self.master_volume;
 
+            // Apply the output EQ cascade (no-op while bypassed/empty)
+            if !self.output_eq_bypass {
+                self.output_left[i] = self.output_eq_left.process(self.output_left[i]);
+                self.output_right[i] = self.output_eq_right.process(self.output_right[i]);
+            }
+
             // Soft clipping only at peaks (gentle, preserves dynamics)
             if self.output_left[i].abs() > 0.95 {
                 self.output_left[i] = self.output_left[i].signum() * (0.95 + (self.output_left[i].abs() - 0.95).tanh() * 0.05);
@@ -295,12 +856,145 @@ impl Sympathetic12 {
         self.reverb.set_damping(damping);
     }
 
+    /// Choose which reverb engine `process` routes through: the default
+    /// `FDNReverb`, or the modulated Dattorro plate reverb
+    #[wasm_bindgen]
+    pub fn set_plate_reverb_enabled(&mut self, enabled: bool) {
+        self.use_plate_reverb = enabled;
+    }
+
+    /// Set the plate reverb's pre-delay, in milliseconds
+    #[wasm_bindgen]
+    pub fn set_plate_reverb_pre_delay_ms(&mut self, ms: f32) {
+        self.plate_reverb.set_pre_delay_ms(ms);
+    }
+
+    /// Set the plate reverb's tank decay/feedback amount (0-1)
+    #[wasm_bindgen]
+    pub fn set_plate_reverb_decay(&mut self, decay: f32) {
+        self.plate_reverb.set_decay(decay);
+    }
+
+    /// Set the plate reverb's input bandwidth lowpass amount (0-1)
+    #[wasm_bindgen]
+    pub fn set_plate_reverb_bandwidth(&mut self, bandwidth: f32) {
+        self.plate_reverb.set_bandwidth(bandwidth);
+    }
+
+    /// Set the plate reverb's tank damping (0-1)
+    #[wasm_bindgen]
+    pub fn set_plate_reverb_damping(&mut self, damping: f32) {
+        self.plate_reverb.set_damping(damping);
+    }
+
+    /// Set the plate reverb's tank delay time scale (0.0025-4x, 1.0 is
+    /// the reference size)
+    #[wasm_bindgen]
+    pub fn set_plate_reverb_time_scale(&mut self, time_scale: f32) {
+        self.plate_reverb.set_time_scale(time_scale);
+    }
+
+    /// Freeze or unfreeze the FDN reverb's tail for infinite sustain
+    #[wasm_bindgen]
+    pub fn set_reverb_freeze(&mut self, freeze: bool) {
+        self.reverb.set_freeze(freeze);
+    }
+
+    /// Set the FDN reverb's internal wet (reverb) output level (0-1),
+    /// independent of `reverb_mix`
+    #[wasm_bindgen]
+    pub fn set_reverb_wet(&mut self, wet: f32) {
+        self.reverb.set_wet(wet);
+    }
+
+    /// Set the FDN reverb's internal dry (input) output level (0-1),
+    /// independent of `reverb_mix`
+    #[wasm_bindgen]
+    pub fn set_reverb_dry(&mut self, dry: f32) {
+        self.reverb.set_dry(dry);
+    }
+
+    /// Set the FDN reverb's pre-delay time in milliseconds
+    #[wasm_bindgen]
+    pub fn set_reverb_pre_delay_ms(&mut self, ms: f32) {
+        self.reverb.set_pre_delay_ms(ms);
+    }
+
+    /// Set the FDN reverb's comb-modulation LFO depth (samples) and rate (Hz)
+    #[wasm_bindgen]
+    pub fn set_reverb_modulation(&mut self, depth: f32, rate: f32) {
+        self.reverb.set_modulation(depth, rate);
+    }
+
     /// Set sympathetic resonance amount (0-1)
     #[wasm_bindgen]
     pub fn set_sympathy_amount(&mut self, amount: f32) {
         self.sympathy_amount = amount.clamp(0.0, 1.0);
     }
 
+    /// Set the sympathetic matrix's scattering-junction coupling gain
+    #[wasm_bindgen]
+    pub fn set_sympathy_coupling_gain(&mut self, gain: f32) {
+        self.sympathy.set_coupling_gain(gain);
+    }
+
+    /// Replace the entire sympathetic coupling matrix (144 row-major
+    /// values, same layout as `get_sympathy_matrix`)
+    #[wasm_bindgen]
+    pub fn set_sympathy_matrix(&mut self, flat: &[f32]) {
+        self.sympathy.set_matrix(flat);
+    }
+
+    /// Set the coupling weight between one source and target pitch class
+    #[wasm_bindgen]
+    pub fn set_sympathy_cell(&mut self, from_pc: usize, to_pc: usize, weight: f32) {
+        self.sympathy.set_coupling(from_pc, to_pc, weight);
+    }
+
+    /// Build a just-intonation coupling matrix (simple frequency ratios
+    /// couple more strongly)
+    #[wasm_bindgen]
+    pub fn matrix_just_intonation(&mut self) {
+        self.sympathy.matrix_just_intonation();
+    }
+
+    /// Build a circle-of-fifths coupling matrix
+    #[wasm_bindgen]
+    pub fn matrix_circle_of_fifths(&mut self) {
+        self.sympathy.matrix_circle_of_fifths();
+    }
+
+    /// Build a uniform coupling matrix at the given strength (0-1)
+    #[wasm_bindgen]
+    pub fn matrix_uniform(&mut self, amount: f32) {
+        self.sympathy.matrix_uniform(amount);
+    }
+
+    /// Set the attack time (seconds) of the per-voice amplitude envelope
+    /// used by melodic (`note_on`/`note_off`) voices
+    #[wasm_bindgen]
+    pub fn set_attack(&mut self, seconds: f32) {
+        self.voice_pool.set_attack(seconds);
+    }
+
+    /// Set the decay time (seconds) of the per-voice amplitude envelope
+    #[wasm_bindgen]
+    pub fn set_decay(&mut self, seconds: f32) {
+        self.voice_pool.set_decay(seconds);
+    }
+
+    /// Set the sustain level (0-1) of the per-voice amplitude envelope
+    #[wasm_bindgen]
+    pub fn set_sustain(&mut self, level: f32) {
+        self.voice_pool.set_sustain(level);
+    }
+
+    /// Set the release time (seconds) of the per-voice amplitude envelope
+    #[wasm_bindgen]
+    pub fn set_release(&mut self, seconds: f32) {
+        self.voice_pool.set_release(seconds);
+    }
+
     /// Set global damping for all strings (0-1)
     #[wasm_bindgen]
     pub fn set_global_damping(&mut self, damping: f32) {
@@ -317,6 +1011,24 @@ impl Sympathetic12 {
         }
     }
 
+    /// Set the per-string pitch detune depth for all strings, as a
+    /// fraction of each string's tuned frequency (0 disables it)
+    #[wasm_bindgen]
+    pub fn set_detune_depth(&mut self, depth: f32) {
+        for string in &mut self.strings {
+            string.set_detune_depth(depth);
+        }
+    }
+
+    /// Set the rate (Hz) at which the detune random walk picks a new
+    /// target, for all strings
+    #[wasm_bindgen]
+    pub fn set_detune_rate(&mut self, hz: f32) {
+        for string in &mut self.strings {
+            string.set_detune_rate(hz);
+        }
+    }
+
     /// Set damping for a specific string
     #[wasm_bindgen]
     pub fn set_string_damping(&mut self, pitch_class: usize, damping: f32) {
@@ -343,6 +1055,40 @@ impl Sympathetic12 {
         }
     }
 
+    /// Glide a string smoothly to a new frequency over `glide_ms`
+    /// milliseconds instead of retuning it instantly
+    #[wasm_bindgen]
+    pub fn set_string_target_frequency(&mut self, pitch_class: usize, freq: f32, glide_ms: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_target_frequency(freq, glide_ms);
+        }
+    }
+
+    /// Set a string's built-in vibrato LFO (`rate_hz`, `depth_cents`)
+    #[wasm_bindgen]
+    pub fn set_string_vibrato(&mut self, pitch_class: usize, rate_hz: f32, depth_cents: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_vibrato(rate_hz, depth_cents);
+        }
+    }
+
+    /// Set how many octaves of deviation a full-scale FM input produces
+    /// on a string
+    #[wasm_bindgen]
+    pub fn set_string_fm_depth(&mut self, pitch_class: usize, octaves: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_fm_depth(octaves);
+        }
+    }
+
+    /// Drive a string's exponential FM input (held until set again)
+    #[wasm_bindgen]
+    pub fn set_string_fm_input(&mut self, pitch_class: usize, signal: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_fm_input(signal);
+        }
+    }
+
     /// Set inharmonicity for a string (simulates stiff strings)
     #[wasm_bindgen]
     pub fn set_string_inharmonicity(&mut self, pitch_class: usize, inharmonicity: f32) {
@@ -351,6 +1097,80 @@ impl Sympathetic12 {
         }
     }
 
+    /// Switch a string's brightness/damping filter between the original
+    /// one-pole lowpass and a state-variable filter, which stays stable
+    /// under continuous audio-rate brightness modulation (e.g. alongside
+    /// a sustained reed/bow excitation)
+    #[wasm_bindgen]
+    pub fn set_string_svf_damping(&mut self, pitch_class: usize, enabled: bool) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_svf_damping(enabled);
+        }
+    }
+
+    /// Set which generator feeds a string's pluck excitation noise
+    /// (0=white, 1=logistic, 2=henon, 3=lorenz, 4=latoocarfian)
+    #[wasm_bindgen]
+    pub fn set_string_excitation_source(&mut self, pitch_class: usize, source: u8) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_excitation_source(excitation_source_from_code(source));
+        }
+    }
+
+    /// Set the pluck excitation source for all strings
+    /// (0=white, 1=logistic, 2=henon, 3=lorenz, 4=latoocarfian)
+    #[wasm_bindgen]
+    pub fn set_global_excitation_source(&mut self, source: u8) {
+        let source = excitation_source_from_code(source);
+        for string in &mut self.strings {
+            string.set_excitation_source(source);
+        }
+    }
+
+    /// Switch a string to a blown (reed) excitation, sustaining the note
+    /// for as long as `pressure` stays above zero
+    #[wasm_bindgen]
+    pub fn set_string_reed_excitation(&mut self, pitch_class: usize, pressure: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_reed_excitation(pressure);
+        }
+    }
+
+    /// Switch a string to a bowed excitation, sustaining the note for as
+    /// long as `velocity`/`force` stay above zero
+    #[wasm_bindgen]
+    pub fn set_string_bow_excitation(&mut self, pitch_class: usize, velocity: f32, force: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_bow_excitation(velocity, force);
+        }
+    }
+
+    /// Update the driving pressure of a string already in reed mode
+    /// (no-op if it isn't)
+    #[wasm_bindgen]
+    pub fn set_string_reed_pressure(&mut self, pitch_class: usize, pressure: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_reed_pressure(pressure);
+        }
+    }
+
+    /// Update the bow velocity/force of a string already in bow mode
+    /// (no-op if it isn't)
+    #[wasm_bindgen]
+    pub fn set_string_bow_drive(&mut self, pitch_class: usize, velocity: f32, force: f32) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_bow_drive(velocity, force);
+        }
+    }
+
+    /// Switch a string back to one-shot plucked excitation
+    #[wasm_bindgen]
+    pub fn set_string_pluck_excitation(&mut self, pitch_class: usize) {
+        if pitch_class < NUM_STRINGS {
+            self.strings[pitch_class].set_pluck_excitation();
+        }
+    }
+
     // ========================================================================
     // State queries (for visualization)
     // ========================================================================
@@ -388,6 +1208,83 @@ impl Sympathetic12 {
             vec![0.0; num_samples]
         }
     }
+
+    // ========================================================================
+    // Patch save/load
+    // ========================================================================
+
+    /// Export the current configuration as a JSON patch string
+    #[wasm_bindgen]
+    pub fn export_patch(&self) -> String {
+        serde_json::to_string(&self.to_patch()).unwrap_or_default()
+    }
+
+    /// Import a configuration from a JSON patch string, leaving the
+    /// current configuration untouched if it fails to parse
+    #[wasm_bindgen]
+    pub fn import_patch(&mut self, json: &str) {
+        if let Ok(patch) = serde_json::from_str::<Patch>(json) {
+            self.apply_patch(&patch);
+        }
+    }
+
+    /// Export the current configuration as a compact binary patch
+    #[wasm_bindgen]
+    pub fn export_patch_bytes(&self) -> Vec<u8> {
+        bincode::serialize(&self.to_patch()).unwrap_or_default()
+    }
+
+    /// Import a configuration from a binary patch, leaving the current
+    /// configuration untouched if it fails to decode
+    #[wasm_bindgen]
+    pub fn import_patch_bytes(&mut self, bytes: &[u8]) {
+        if let Ok(patch) = bincode::deserialize::<Patch>(bytes) {
+            self.apply_patch(&patch);
+        }
+    }
+
+    /// Gather every tweakable parameter into a serializable `Patch`
+    fn to_patch(&self) -> Patch {
+        Patch {
+            master_volume: self.master_volume,
+            reverb_mix: self.reverb_mix,
+            reverb_size: self.reverb.get_room_size(),
+            reverb_damping: self.reverb.get_damping(),
+            sympathy_amount: self.sympathy_amount,
+            base_octave: self.base_octave,
+            strings: self
+                .strings
+                .iter()
+                .map(|s| StringPatch {
+                    damping: s.get_damping(),
+                    brightness: s.get_brightness(),
+                    frequency: s.get_frequency(),
+                    inharmonicity: s.get_inharmonicity(),
+                })
+                .collect(),
+            sympathy_matrix: self.sympathy.get_matrix(),
+        }
+    }
+
+    /// Apply every parameter from a `Patch`, restoring a previously
+    /// exported configuration
+    fn apply_patch(&mut self, patch: &Patch) {
+        self.master_volume = patch.master_volume.clamp(0.0, 1.0);
+        self.reverb_mix = patch.reverb_mix.clamp(0.0, 1.0);
+        self.reverb.set_room_size(patch.reverb_size);
+        self.reverb.set_damping(patch.reverb_damping);
+        self.sympathy_amount = patch.sympathy_amount.clamp(0.0, 1.0);
+        self.base_octave = patch.base_octave;
+
+        for (string, string_patch) in self.strings.iter_mut().zip(&patch.strings) {
+            string.set_damping(string_patch.damping);
+            string.set_brightness(string_patch.brightness);
+            string.set_frequency(string_patch.frequency);
+            string.set_inharmonicity(string_patch.inharmonicity);
+        }
+
+        self.sympathy.set_matrix(&patch.sympathy_matrix);
+    }
 }
 
 impl Default for Sympathetic12 {
@@ -410,6 +1307,10 @@ impl Sympathetic12 {
         self.sympathy_amount = 0.2;
         self.reverb_mix = 0.15;
         self.reverb.set_room_size(0.4);
+        self.set_attack(0.002);
+        self.set_decay(0.15);
+        self.set_sustain(0.5);
+        self.set_release(0.3);
     }
 
     /// Preset: Harp-like (low damping, high sympathy)
@@ -420,6 +1321,8 @@ impl Sympathetic12 {
         self.sympathy_amount = 0.5;
         self.reverb_mix = 0.3;
         self.reverb.set_room_size(0.6);
+        self.set_detune_depth(0.004);
+        self.set_detune_rate(3.0);
     }
 
     /// Preset: Guitar-like (medium damping, low sympathy)
@@ -453,6 +1356,10 @@ impl Sympathetic12 {
         }
         self.reverb_mix = 0.4;
         self.reverb.set_room_size(0.8);
+        self.set_attack(0.001);
+        self.set_decay(0.4);
+        self.set_sustain(0.3);
+        self.set_release(1.5);
     }
 
     /// Preset: Pad-like (maximum sustain and sympathy)
@@ -463,5 +1370,11 @@ impl Sympathetic12 {
         self.sympathy_amount = 0.9;
         self.reverb_mix = 0.6;
         self.reverb.set_room_size(0.95);
+        self.set_attack(0.8);
+        self.set_decay(0.5);
+        self.set_sustain(0.9);
+        self.set_release(2.5);
+        self.set_detune_depth(0.006);
+        self.set_detune_rate(2.0);
     }
 }
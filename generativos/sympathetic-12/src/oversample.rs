@@ -0,0 +1,171 @@
+//! Oversampled nonlinear processing
+//!
+//! Static nonlinearities (saturators, reed tables, ...) generate harmonics
+//! above the input's bandwidth, which fold back as aliasing when run at the
+//! host sample rate. `Oversampler` runs an arbitrary closure at 2x or 4x the
+//! host rate and band-limits the result on the way back down, using
+//! half-band FIR filters for both directions. The filters run at the full
+//! oversampled rate (every sub-sample is filtered, and the decimator then
+//! drops every other output); they are not split into polyphase branches.
+
+/// Number of taps in the half-band FIR kernel (odd length, symmetric)
+const HB_TAPS: usize = 23;
+
+/// Maximum oversampling factor supported
+const MAX_FACTOR: usize = 4;
+
+/// A half-band low-pass FIR filter
+///
+/// Half-band filters have every tap zero except the center tap and the
+/// odd-offset taps, so only those nonzero taps are stored and multiplied,
+/// halving the work a direct-form FIR of the same length would need.
+struct HalfbandFilter {
+    /// `(ring-buffer age, coefficient)` pairs for the nonzero taps only
+    taps: Vec<(usize, f32)>,
+    /// Ring buffer holding the last `HB_TAPS` input samples
+    history: [f32; HB_TAPS],
+    write_pos: usize,
+}
+
+impl HalfbandFilter {
+    /// Build a half-band filter whose cutoff (its -6 dB point) sits at
+    /// half the Nyquist frequency of the rate it runs at, which for the
+    /// first 2x stage is the base (pre-oversampling) Nyquist frequency,
+    /// windowed with a Hamming window to control stopband ripple.
+    fn new() -> Self {
+        let center = HB_TAPS / 2;
+        let mut kernel = [0.0f32; HB_TAPS];
+        for (i, coeff) in kernel.iter_mut().enumerate() {
+            let n = i as i32 - center as i32;
+            *coeff = if n == 0 {
+                0.5
+            } else if n % 2 != 0 {
+                let x = std::f32::consts::PI * n as f32 / 2.0;
+                let sinc = x.sin() / x;
+                let window =
+                    0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (HB_TAPS - 1) as f32).cos();
+                0.5 * sinc * window
+            } else {
+                0.0
+            };
+        }
+
+        let taps = kernel
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c != 0.0)
+            .map(|(i, &c)| ((HB_TAPS - 1) - i, c))
+            .collect();
+
+        HalfbandFilter {
+            taps,
+            history: [0.0; HB_TAPS],
+            write_pos: 0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        self.history[self.write_pos] = input;
+
+        let mut acc = 0.0f32;
+        for &(age, coeff) in &self.taps {
+            let read_pos = (self.write_pos + HB_TAPS - age) % HB_TAPS;
+            acc += self.history[read_pos] * coeff;
+        }
+
+        self.write_pos = (self.write_pos + 1) % HB_TAPS;
+        acc
+    }
+
+    fn reset(&mut self) {
+        self.history = [0.0; HB_TAPS];
+        self.write_pos = 0;
+    }
+}
+
+/// One 2x interpolation/decimation stage (stages cascade for 4x)
+struct Stage {
+    interpolator: HalfbandFilter,
+    decimator: HalfbandFilter,
+}
+
+/// Runs a closure at 2x or 4x the host sample rate with band-limited
+/// interpolation/decimation on either side
+///
+/// Wrap a hard nonlinearity (a `tanh` saturator, a reed reflection table,
+/// ...) in `process` to keep its aliased harmonics out of the audible
+/// band instead of processing it at the host rate directly.
+pub struct Oversampler {
+    stages: Vec<Stage>,
+}
+
+impl Oversampler {
+    /// Create a new oversampler running at `factor` times the host rate.
+    /// Supported factors are 2 and 4 (anything else is clamped to the
+    /// nearest supported factor).
+    pub fn new(factor: usize) -> Self {
+        let num_stages = if factor >= 4 { 2 } else { 1 };
+        let stages = (0..num_stages)
+            .map(|_| Stage {
+                interpolator: HalfbandFilter::new(),
+                decimator: HalfbandFilter::new(),
+            })
+            .collect();
+
+        Oversampler { stages }
+    }
+
+    /// Upsample one input sample, apply `f` to each sub-sample, then
+    /// decimate back down to one output sample
+    ///
+    /// Phase delay stays constant regardless of how many times `process`
+    /// is called, since both directions run through the same fixed-length
+    /// half-band filters every call.
+    pub fn process<F: FnMut(f32) -> f32>(&mut self, input: f32, mut f: F) -> f32 {
+        let mut buf = [0.0f32; MAX_FACTOR];
+        let mut len = 1;
+        buf[0] = input;
+
+        // Interpolate: zero-stuff by 2x per stage and low-pass filter
+        for stage in &mut self.stages {
+            let mut up = [0.0f32; MAX_FACTOR];
+            for i in 0..len {
+                up[2 * i] = stage.interpolator.process(buf[i] * 2.0);
+                up[2 * i + 1] = stage.interpolator.process(0.0);
+            }
+            len *= 2;
+            buf = up;
+        }
+
+        for sample in buf.iter_mut().take(len) {
+            *sample = f(*sample);
+        }
+
+        // Decimate: low-pass filter the full-rate stream, then drop samples
+        for stage in self.stages.iter_mut().rev() {
+            let mut down = [0.0f32; MAX_FACTOR];
+            let mut out_len = 0;
+            let mut i = 0;
+            while i < len {
+                let kept = stage.decimator.process(buf[i]);
+                stage.decimator.process(buf[i + 1]);
+                down[out_len] = kept;
+                out_len += 1;
+                i += 2;
+            }
+            len = out_len;
+            buf = down;
+        }
+
+        buf[0]
+    }
+
+    /// Reset all internal filter state
+    pub fn reset(&mut self) {
+        for stage in &mut self.stages {
+            stage.interpolator.reset();
+            stage.decimator.reset();
+        }
+    }
+}
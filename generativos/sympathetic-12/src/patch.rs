@@ -0,0 +1,60 @@
+//! Serializable instrument patches (user presets)
+//!
+//! Captures every tweakable parameter of `Sympathetic12` into a `Patch`
+//! so a full configuration can be exported, stored, and reloaded, round
+//! -tripping the sympathetic coupling matrix -- which the built-in
+//! `preset_*` methods only ever write to, with no way to read a custom
+//! one back in.
+
+use serde::{Deserialize, Serialize};
+
+use crate::resonance::SympatheticMatrix;
+
+/// Per-string tweakable parameters
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct StringPatch {
+    pub damping: f32,
+    pub brightness: f32,
+    pub frequency: f32,
+    pub inharmonicity: f32,
+}
+
+/// A complete, serializable instrument configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Patch {
+    pub master_volume: f32,
+    pub reverb_mix: f32,
+    pub reverb_size: f32,
+    pub reverb_damping: f32,
+    pub sympathy_amount: f32,
+    pub base_octave: i32,
+    /// One entry per string (length `NUM_STRINGS`)
+    pub strings: Vec<StringPatch>,
+    /// Flat, row-major `NUM_STRINGS * NUM_STRINGS` coupling matrix
+    pub sympathy_matrix: Vec<f32>,
+}
+
+impl Patch {
+    /// A patch matching `Sympathetic12::new`'s defaults, for callers
+    /// that want a baseline to tweak before importing
+    pub fn default_for_strings(frequencies: &[f32]) -> Self {
+        Patch {
+            master_volume: 0.7,
+            reverb_mix: 0.25,
+            reverb_size: 0.5,
+            reverb_damping: 0.5,
+            sympathy_amount: 0.4,
+            base_octave: 3,
+            strings: frequencies
+                .iter()
+                .map(|&frequency| StringPatch {
+                    damping: 0.998,
+                    brightness: 0.5,
+                    frequency,
+                    inharmonicity: 0.0,
+                })
+                .collect(),
+            sympathy_matrix: SympatheticMatrix::new().get_matrix(),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! High-level phrase rendering
+//!
+//! Compiles a sequence of `PhraseNote`s plus phrase-shaping
+//! `PhraseAttribute`s into scheduled pluck/damp events on a `Sequencer`,
+//! so a composer can hand the instrument a musical phrase instead of
+//! driving `pluck`/`damp` one call at a time.
+
+use crate::sequencer::Sequencer;
+
+/// Named dynamic levels, mapped onto velocity (and, by the caller, onto
+/// `set_global_brightness`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dynamic {
+    Pp,
+    P,
+    Mp,
+    Mf,
+    F,
+    Ff,
+}
+
+impl Dynamic {
+    /// The velocity (0-1) this dynamic level maps onto
+    pub fn velocity(self) -> f32 {
+        match self {
+            Dynamic::Pp => 0.15,
+            Dynamic::P => 0.3,
+            Dynamic::Mp => 0.45,
+            Dynamic::Mf => 0.6,
+            Dynamic::F => 0.75,
+            Dynamic::Ff => 0.9,
+        }
+    }
+
+    /// Map a numeric code (0=pp .. 5=ff, clamped) onto a `Dynamic`
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 => Dynamic::Pp,
+            1 => Dynamic::P,
+            2 => Dynamic::Mp,
+            3 => Dynamic::Mf,
+            4 => Dynamic::F,
+            _ => Dynamic::Ff,
+        }
+    }
+}
+
+/// One note in a phrase
+#[derive(Clone, Copy, Debug)]
+pub struct PhraseNote {
+    pub pitch_class: usize,
+    pub octave: i32,
+    pub start_beat: f64,
+    pub duration_beats: f64,
+    pub dynamic: Dynamic,
+}
+
+/// A phrase-wide shaping attribute
+#[derive(Clone, Copy, Debug)]
+pub enum PhraseAttribute {
+    /// Shorten each note's sounding duration to this fraction (0-1) of
+    /// its written duration, damping it early
+    Staccato(f32),
+    /// Boost the velocity of the phrase's first note
+    Accent,
+    /// Interpolate the dynamic linearly across the phrase, overriding
+    /// each note's own `dynamic`
+    Crescendo(Dynamic, Dynamic),
+    /// Let consecutive notes ring into each other instead of damping
+    /// at the end of each note's duration
+    Legato,
+}
+
+/// Compile `notes` and `attributes` into scheduled pluck/damp events on
+/// `sequencer`. Returns the phrase's average velocity, for the caller
+/// to shape e.g. `set_global_brightness` from.
+pub fn render_phrase(sequencer: &mut Sequencer, notes: &[PhraseNote], attributes: &[PhraseAttribute]) -> f32 {
+    if notes.is_empty() {
+        return 0.0;
+    }
+
+    let staccato_factor = attributes.iter().find_map(|a| match a {
+        PhraseAttribute::Staccato(factor) => Some(factor.clamp(0.0, 1.0)),
+        _ => None,
+    });
+    let crescendo = attributes.iter().find_map(|a| match a {
+        PhraseAttribute::Crescendo(start, end) => Some((*start, *end)),
+        _ => None,
+    });
+    let accented = attributes.iter().any(|a| matches!(a, PhraseAttribute::Accent));
+    let legato = attributes.iter().any(|a| matches!(a, PhraseAttribute::Legato));
+
+    let phrase_start = notes[0].start_beat;
+    let phrase_span = notes
+        .iter()
+        .fold(phrase_start, |end, n| end.max(n.start_beat + n.duration_beats))
+        - phrase_start;
+    let phrase_span = phrase_span.max(1e-6);
+
+    let mut velocity_sum = 0.0f32;
+
+    for (i, note) in notes.iter().enumerate() {
+        let mut velocity = match crescendo {
+            Some((start, end)) => {
+                let t = ((note.start_beat - phrase_start) / phrase_span) as f32;
+                start.velocity() + (end.velocity() - start.velocity()) * t.clamp(0.0, 1.0)
+            }
+            None => note.dynamic.velocity(),
+        };
+        if accented && i == 0 {
+            velocity = (velocity * 1.3).clamp(0.0, 1.0);
+        }
+        velocity_sum += velocity;
+
+        // Fold octave into the fixed 12 pitch-class strings, the same
+        // way `pluck`/`pluck_prime_form` address them
+        let midi_note = note.pitch_class as i32 + (note.octave + 1) * 12;
+        let pitch_class = midi_note.rem_euclid(12) as usize;
+
+        sequencer.schedule_pluck(note.start_beat, pitch_class, velocity, 0.5);
+
+        if !legato {
+            let sounding_beats = match staccato_factor {
+                Some(factor) => note.duration_beats * factor as f64,
+                None => note.duration_beats,
+            };
+            sequencer.schedule_damp(note.start_beat + sounding_beats, pitch_class, 0.6);
+        }
+    }
+
+    velocity_sum / notes.len() as f32
+}
@@ -0,0 +1,190 @@
+//! Monophonic pitch detection and autotune-style voice driving
+//!
+//! Contains:
+//! - `yin_pitch` - fundamental frequency estimator using the YIN algorithm
+//! - `PitchMode` - whether detected pitch snaps to the nearest string or
+//!   follows an externally supplied MIDI note
+//! - `PitchCorrector` - drives string selection from a monophonic input
+//!   buffer, modeled on the manual/snap behavior of classic autotune
+//!   hardware (e.g. the Antares ATR-1 "robotuna")
+
+use crate::NUM_STRINGS;
+
+/// Lowest fundamental YIN will search for, in Hz
+const MIN_FREQUENCY: f32 = 50.0;
+
+/// Highest fundamental YIN will search for, in Hz
+const MAX_FREQUENCY: f32 = 1000.0;
+
+/// Cumulative-mean-normalized-difference threshold below which a lag is
+/// accepted as the fundamental period
+const YIN_THRESHOLD: f32 = 0.1;
+
+/// Result of a single pitch-detection pass
+#[derive(Clone, Copy, Debug)]
+pub struct PitchEstimate {
+    /// Detected fundamental frequency in Hz, or `None` if no lag in
+    /// range ever dipped below the threshold (unvoiced/silent input)
+    pub frequency: Option<f32>,
+    /// Confidence in `[0, 1]`: one minus the chosen lag's normalized
+    /// difference (1.0 = perfect periodicity)
+    pub confidence: f32,
+}
+
+/// Estimate the fundamental frequency of `buffer` using the YIN algorithm
+///
+/// Searches lags covering `MIN_FREQUENCY`..`MAX_FREQUENCY` at the given
+/// sample rate. `buffer` should be at least two periods of the lowest
+/// frequency of interest long (e.g. ~1764 samples at 44.1 kHz to cover a
+/// 50 Hz fundamental).
+pub fn yin_pitch(buffer: &[f32], sample_rate: f32) -> PitchEstimate {
+    let min_lag = (sample_rate / MAX_FREQUENCY).floor().max(1.0) as usize;
+    let max_lag = ((sample_rate / MIN_FREQUENCY).ceil() as usize).min(buffer.len() / 2);
+
+    if max_lag <= min_lag || buffer.len() < max_lag * 2 {
+        return PitchEstimate { frequency: None, confidence: 0.0 };
+    }
+
+    // Difference function d(tau) = sum((x[n] - x[n+tau])^2)
+    let mut diff = vec![0.0f32; max_lag + 1];
+    for tau in 1..=max_lag {
+        let mut sum = 0.0;
+        for n in 0..(buffer.len() - tau) {
+            let delta = buffer[n] - buffer[n + tau];
+            sum += delta * delta;
+        }
+        diff[tau] = sum;
+    }
+
+    // Cumulative mean normalized difference: d'(tau) = d(tau) * tau / sum(d(1..=tau))
+    let mut cmnd = vec![1.0f32; max_lag + 1];
+    let mut running_sum = 0.0;
+    for tau in 1..=max_lag {
+        running_sum += diff[tau];
+        cmnd[tau] = if running_sum > 0.0 {
+            diff[tau] * tau as f32 / running_sum
+        } else {
+            1.0
+        };
+    }
+
+    // First local minimum below threshold, searching from min_lag
+    let mut chosen_tau = None;
+    let mut tau = min_lag.max(1);
+    while tau < max_lag {
+        if cmnd[tau] < YIN_THRESHOLD {
+            while tau + 1 < max_lag && cmnd[tau + 1] < cmnd[tau] {
+                tau += 1;
+            }
+            chosen_tau = Some(tau);
+            break;
+        }
+        tau += 1;
+    }
+
+    let chosen_tau = match chosen_tau {
+        Some(t) => t,
+        None => return PitchEstimate { frequency: None, confidence: 0.0 },
+    };
+
+    // Parabolic interpolation around the chosen lag for sub-sample accuracy
+    let refined_tau = if chosen_tau > min_lag && chosen_tau + 1 < max_lag {
+        let (y0, y1, y2) = (cmnd[chosen_tau - 1], cmnd[chosen_tau], cmnd[chosen_tau + 1]);
+        let denom = 2.0 * (y0 - 2.0 * y1 + y2);
+        if denom.abs() > f32::EPSILON {
+            chosen_tau as f32 + (y0 - y2) / denom
+        } else {
+            chosen_tau as f32
+        }
+    } else {
+        chosen_tau as f32
+    };
+
+    let frequency = sample_rate / refined_tau;
+    let confidence = (1.0 - cmnd[chosen_tau]).clamp(0.0, 1.0);
+
+    PitchEstimate { frequency: Some(frequency), confidence }
+}
+
+/// How `PitchCorrector` chooses which string to drive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PitchMode {
+    /// Quantize the detected frequency to the nearest equal-tempered
+    /// semitone and drive the corresponding string
+    Snap,
+    /// Ignore the detected frequency and drive whatever MIDI note was
+    /// last supplied via `set_manual_note`
+    Manual,
+}
+
+/// Result of running `PitchCorrector::process`
+#[derive(Clone, Copy, Debug)]
+pub struct PitchCorrectorResult {
+    /// String (pitch class, 0-11) chosen for this analysis window
+    pub string_index: usize,
+    /// Detection confidence in `[0, 1]` (always 1.0 in `Manual` mode)
+    pub confidence: f32,
+}
+
+/// Drives voice allocation from detected or manually supplied pitch
+pub struct PitchCorrector {
+    /// Sample rate of the input buffer
+    sample_rate: f32,
+    /// Snap-to-string vs follow-manual-note behavior
+    mode: PitchMode,
+    /// Multiplier applied to the target frequency before snapping
+    /// (e.g. 2.0 shifts the detected pitch up an octave)
+    frequency_gain: f32,
+    /// Last manually supplied MIDI note (used in `Manual` mode)
+    manual_note: u8,
+}
+
+impl PitchCorrector {
+    /// Create a new pitch corrector for the given sample rate
+    pub fn new(sample_rate: f32) -> Self {
+        PitchCorrector {
+            sample_rate,
+            mode: PitchMode::Snap,
+            frequency_gain: 1.0,
+            manual_note: 69, // A4
+        }
+    }
+
+    /// Set the detection/driving mode
+    pub fn set_mode(&mut self, mode: PitchMode) {
+        self.mode = mode;
+    }
+
+    /// Set the frequency multiplier applied before snapping
+    pub fn set_frequency_gain(&mut self, gain: f32) {
+        self.frequency_gain = gain.max(0.0);
+    }
+
+    /// Supply the MIDI note followed in `Manual` mode
+    pub fn set_manual_note(&mut self, midi_note: u8) {
+        self.manual_note = midi_note;
+    }
+
+    /// Analyze one buffer of monophonic input and choose a string
+    ///
+    /// In `Snap` mode, detects the fundamental with YIN, applies
+    /// `frequency_gain`, and quantizes to the nearest equal-tempered
+    /// semitone via `12*log2(f/440)+69` rounded to the nearest MIDI
+    /// note; the string is that note's pitch class. In `Manual` mode,
+    /// the buffer is ignored and the string comes from `manual_note`.
+    pub fn process(&mut self, buffer: &[f32]) -> Option<PitchCorrectorResult> {
+        match self.mode {
+            PitchMode::Manual => Some(PitchCorrectorResult {
+                string_index: (self.manual_note as usize) % NUM_STRINGS,
+                confidence: 1.0,
+            }),
+            PitchMode::Snap => {
+                let estimate = yin_pitch(buffer, self.sample_rate);
+                let frequency = estimate.frequency? * self.frequency_gain;
+                let midi_note = (12.0 * (frequency / 440.0).log2() + 69.0).round();
+                let string_index = (midi_note.rem_euclid(12.0)) as usize % NUM_STRINGS;
+                Some(PitchCorrectorResult { string_index, confidence: estimate.confidence })
+            }
+        }
+    }
+}
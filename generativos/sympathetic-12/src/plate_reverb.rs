@@ -0,0 +1,370 @@
+//! Dattorro plate reverb (modulated figure-eight tank)
+//!
+//! An alternative to `FDNReverb`'s fixed comb/allpass bank. Sustained
+//! tones through a static comb network tend to sound metallic; the
+//! classic Dattorro topology breaks that up by running a figure-eight
+//! loop of two symmetric halves, each containing a modulated allpass
+//! diffuser, so the tank's resonances drift continuously instead of
+//! locking onto fixed comb frequencies.
+//!
+//! Signal flow: mono input -> pre-delay -> input bandwidth lowpass ->
+//! four series allpass diffusers -> figure-eight tank (two halves, each
+//! feeding the other) -> summed fixed taps on the tank's delay lines for
+//! stereo output -> DC-blocking highpass.
+
+use crate::filters::{DCBlocker, DelayBuffer, Interpolation, OnePole};
+
+/// Reference sample rate the published Dattorro delay times are tuned for
+const REFERENCE_SAMPLE_RATE: f32 = 29760.0;
+
+/// Maximum `set_time_scale` factor (tank delays can grow up to 4x)
+const MAX_TIME_SCALE: f32 = 4.0;
+
+/// Maximum pre-delay, in seconds
+const MAX_PRE_DELAY_SECONDS: f32 = 5.0;
+
+/// A slow sine LFO used to modulate a tank allpass delay length
+struct Lfo {
+    phase: f32,
+    rate_hz: f32,
+}
+
+impl Lfo {
+    fn new(rate_hz: f32) -> Self {
+        Lfo { phase: 0.0, rate_hz }
+    }
+
+    #[inline]
+    fn next(&mut self, sample_rate: f32) -> f32 {
+        let value = (2.0 * std::f32::consts::PI * self.phase).sin();
+        self.phase += self.rate_hz / sample_rate;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        }
+        value
+    }
+}
+
+/// A fixed-coefficient Schroeder allpass diffuser with a fixed delay
+struct FixedAllpass {
+    delay_line: DelayBuffer,
+    delay: f32,
+    coefficient: f32,
+}
+
+impl FixedAllpass {
+    fn new(delay_samples: f32, coefficient: f32, max_delay: usize) -> Self {
+        FixedAllpass {
+            delay_line: DelayBuffer::new(max_delay, Interpolation::Linear),
+            delay: delay_samples,
+            coefficient,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.delay_line.read_frac(self.delay);
+        let output = -self.coefficient * input + delayed;
+        self.delay_line.write(input + delayed * self.coefficient);
+        output
+    }
+
+    fn clear(&mut self) {
+        self.delay_line.clear();
+    }
+}
+
+/// A Schroeder allpass diffuser whose delay length is modulated every
+/// sample by an LFO, so the tank's resonances "breathe" instead of
+/// settling on fixed frequencies
+struct ModulatedAllpass {
+    delay_line: DelayBuffer,
+    base_delay: f32,
+    mod_depth: f32,
+    coefficient: f32,
+}
+
+impl ModulatedAllpass {
+    fn new(base_delay_samples: f32, mod_depth_samples: f32, coefficient: f32, max_delay: usize) -> Self {
+        ModulatedAllpass {
+            delay_line: DelayBuffer::new(max_delay, Interpolation::Cubic),
+            base_delay: base_delay_samples,
+            mod_depth: mod_depth_samples,
+            coefficient,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32, lfo: f32) -> f32 {
+        let delay = (self.base_delay + lfo * self.mod_depth).max(1.0);
+        let delayed = self.delay_line.read_frac(delay);
+        let output = -self.coefficient * input + delayed;
+        self.delay_line.write(input + delayed * self.coefficient);
+        output
+    }
+
+    fn clear(&mut self) {
+        self.delay_line.clear();
+    }
+}
+
+/// A plain fixed delay that also supports reading extra taps at other
+/// offsets, for the tank's stereo output points
+struct TappedDelay {
+    delay_line: DelayBuffer,
+    delay: f32,
+}
+
+impl TappedDelay {
+    fn new(delay_samples: f32, max_delay: usize) -> Self {
+        TappedDelay {
+            delay_line: DelayBuffer::new(max_delay, Interpolation::Linear),
+            delay: delay_samples,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.delay_line.read_frac(self.delay);
+        self.delay_line.write(input);
+        output
+    }
+
+    /// Read an extra tap at a fixed offset, without advancing the line
+    #[inline]
+    fn tap(&mut self, offset: f32) -> f32 {
+        self.delay_line.read_frac(offset)
+    }
+
+    fn clear(&mut self) {
+        self.delay_line.clear();
+    }
+}
+
+/// One half of the figure-eight tank: excursion diffuser -> delay ->
+/// damping -> decay -> fixed diffuser -> delay
+struct TankHalf {
+    excursion: ModulatedAllpass,
+    delay_a: TappedDelay,
+    damping: OnePole,
+    diffuser: FixedAllpass,
+    delay_b: TappedDelay,
+}
+
+impl TankHalf {
+    fn clear(&mut self) {
+        self.excursion.clear();
+        self.delay_a.clear();
+        self.damping.reset();
+        self.diffuser.clear();
+        self.delay_b.clear();
+    }
+}
+
+/// Modulated Dattorro plate reverb
+pub struct DattorroReverb {
+    sample_rate: f32,
+    scale: f32,
+
+    pre_delay_line: DelayBuffer,
+    pre_delay_samples: f32,
+
+    input_lowpass: OnePole,
+    input_diffusers: [FixedAllpass; 4],
+
+    half_a: TankHalf,
+    half_b: TankHalf,
+
+    lfos: [Lfo; 4],
+
+    output_dc_blocker_left: DCBlocker,
+    output_dc_blocker_right: DCBlocker,
+
+    decay: f32,
+    bandwidth: f32,
+    damping: f32,
+    time_scale: f32,
+}
+
+impl DattorroReverb {
+    /// Create a new Dattorro plate reverb at the given sample rate
+    pub fn new(sample_rate: f32) -> Self {
+        let scale = sample_rate / REFERENCE_SAMPLE_RATE;
+        let max_scale = scale * MAX_TIME_SCALE;
+
+        let sized = |base: f32| ((base * max_scale) as usize) + 8;
+
+        let input_diffusers = [
+            FixedAllpass::new(142.0 * scale, 0.75, sized(142.0)),
+            FixedAllpass::new(107.0 * scale, 0.75, sized(107.0)),
+            FixedAllpass::new(379.0 * scale, 0.625, sized(379.0)),
+            FixedAllpass::new(277.0 * scale, 0.625, sized(277.0)),
+        ];
+
+        let half_a = TankHalf {
+            excursion: ModulatedAllpass::new(672.0 * scale, 12.0 * scale, -0.7, sized(672.0) + 32),
+            delay_a: TappedDelay::new(4453.0 * scale, sized(4453.0)),
+            damping: OnePole::new(0.2),
+            diffuser: FixedAllpass::new(1800.0 * scale, 0.5, sized(1800.0)),
+            delay_b: TappedDelay::new(3720.0 * scale, sized(3720.0)),
+        };
+
+        let half_b = TankHalf {
+            excursion: ModulatedAllpass::new(908.0 * scale, 12.0 * scale, -0.7, sized(908.0) + 32),
+            delay_a: TappedDelay::new(4217.0 * scale, sized(4217.0)),
+            damping: OnePole::new(0.2),
+            diffuser: FixedAllpass::new(2656.0 * scale, 0.5, sized(2656.0)),
+            delay_b: TappedDelay::new(3163.0 * scale, sized(3163.0)),
+        };
+
+        let max_pre_delay = (MAX_PRE_DELAY_SECONDS * sample_rate) as usize + 1;
+
+        DattorroReverb {
+            sample_rate,
+            scale,
+            pre_delay_line: DelayBuffer::new(max_pre_delay, Interpolation::Linear),
+            pre_delay_samples: 0.0,
+            input_lowpass: OnePole::new(0.2),
+            input_diffusers,
+            half_a,
+            half_b,
+            lfos: [
+                Lfo::new(0.1),
+                Lfo::new(0.15),
+                Lfo::new(0.12),
+                Lfo::new(0.18),
+            ],
+            output_dc_blocker_left: DCBlocker::new(10.0, sample_rate),
+            output_dc_blocker_right: DCBlocker::new(10.0, sample_rate),
+            decay: 0.5,
+            bandwidth: 0.8,
+            damping: 0.5,
+            time_scale: 1.0,
+        }
+    }
+
+    /// Process one mono input sample and return a stereo output pair
+    pub fn process(&mut self, input: f32) -> (f32, f32) {
+        let pre_delayed = self.pre_delay_line.read_frac(self.pre_delay_samples);
+        self.pre_delay_line.write(input);
+
+        let bandwidth_filtered = self.input_lowpass.process(pre_delayed);
+
+        let mut diffused = bandwidth_filtered;
+        for diffuser in &mut self.input_diffusers {
+            diffused = diffuser.process(diffused);
+        }
+
+        // Each half's excursion allpass is driven by a blend of two of the
+        // four LFOs, so all four contribute to the tank's breathing
+        let mod_a = self.lfos[0].next(self.sample_rate) + self.lfos[2].next(self.sample_rate) * 0.5;
+        let mod_b = self.lfos[1].next(self.sample_rate) + self.lfos[3].next(self.sample_rate) * 0.5;
+
+        // Figure eight: the end of half A's second delay feeds half B's
+        // input and vice versa, read from the previous sample's state to
+        // close the loop
+        let end_of_a_delay = self.half_a.delay_b.delay;
+        let end_of_b_delay = self.half_b.delay_b.delay;
+        let feed_into_a = diffused + self.half_b.delay_b.tap(end_of_b_delay) * self.decay;
+        let feed_into_b = diffused + self.half_a.delay_b.tap(end_of_a_delay) * self.decay;
+
+        let a1 = self.half_a.excursion.process(feed_into_a, mod_a);
+        let a2 = self.half_a.delay_a.process(a1);
+        let a3 = self.half_a.damping.process(a2);
+        let a4 = a3 * self.decay;
+        let a5 = self.half_a.diffuser.process(a4);
+        self.half_a.delay_b.process(a5);
+
+        let b1 = self.half_b.excursion.process(feed_into_b, mod_b);
+        let b2 = self.half_b.delay_a.process(b1);
+        let b3 = self.half_b.damping.process(b2);
+        let b4 = b3 * self.decay;
+        let b5 = self.half_b.diffuser.process(b4);
+        self.half_b.delay_b.process(b5);
+
+        // Distinct left/right tap sets at fixed offsets into the tank
+        // delay lines, with alternating signs, as in the reference design
+        let left = self.half_a.delay_b.tap(266.0 * self.scale) + self.half_a.delay_a.tap(2974.0 * self.scale)
+            - self.half_b.delay_b.tap(1913.0 * self.scale)
+            + self.half_b.delay_a.tap(1996.0 * self.scale)
+            - self.half_a.delay_b.tap(187.0 * self.scale);
+
+        let right = self.half_b.delay_b.tap(353.0 * self.scale) + self.half_b.delay_a.tap(3627.0 * self.scale)
+            - self.half_a.delay_b.tap(1228.0 * self.scale)
+            + self.half_a.delay_a.tap(2673.0 * self.scale)
+            - self.half_b.delay_b.tap(335.0 * self.scale);
+
+        let mut left = self.output_dc_blocker_left.process(left);
+        let mut right = self.output_dc_blocker_right.process(right);
+
+        if !left.is_finite() {
+            left = 0.0;
+        }
+        if !right.is_finite() {
+            right = 0.0;
+        }
+
+        (left.clamp(-1.0, 1.0), right.clamp(-1.0, 1.0))
+    }
+
+    /// Set pre-delay time in milliseconds
+    pub fn set_pre_delay_ms(&mut self, ms: f32) {
+        let max_ms = MAX_PRE_DELAY_SECONDS * 1000.0;
+        self.pre_delay_samples = (ms.clamp(0.0, max_ms) / 1000.0) * self.sample_rate;
+    }
+
+    /// Set the tank decay factor (0-1, higher sustains longer)
+    pub fn set_decay(&mut self, decay: f32) {
+        self.decay = decay.clamp(0.0, 1.0);
+    }
+
+    /// Set the input bandwidth (0-1, lower darkens the input into the tank)
+    pub fn set_bandwidth(&mut self, bandwidth: f32) {
+        self.bandwidth = bandwidth.clamp(0.0, 1.0);
+        self.input_lowpass.set_coefficient(1.0 - self.bandwidth);
+    }
+
+    /// Set the tank damping (0-1, higher darkens the decaying tail faster)
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 1.0);
+        self.half_a.damping.set_coefficient(self.damping);
+        self.half_b.damping.set_coefficient(self.damping);
+    }
+
+    /// Scale all tank delay lengths, from a tiny plate (~0.0025) to a
+    /// cavern (~4.0). 1.0 is the reference room size.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        let time_scale = time_scale.clamp(0.0025, MAX_TIME_SCALE);
+        self.time_scale = time_scale;
+        let s = self.scale * time_scale;
+
+        self.half_a.excursion.base_delay = 672.0 * s;
+        self.half_a.delay_a.delay = 4453.0 * s;
+        self.half_a.diffuser.delay = 1800.0 * s;
+        self.half_a.delay_b.delay = 3720.0 * s;
+
+        self.half_b.excursion.base_delay = 908.0 * s;
+        self.half_b.delay_a.delay = 4217.0 * s;
+        self.half_b.diffuser.delay = 2656.0 * s;
+        self.half_b.delay_b.delay = 3163.0 * s;
+    }
+
+    /// Clear all internal buffers and filter state
+    pub fn clear(&mut self) {
+        self.pre_delay_line.clear();
+        self.input_lowpass.reset();
+        for diffuser in &mut self.input_diffusers {
+            diffuser.clear();
+        }
+        self.half_a.clear();
+        self.half_b.clear();
+        self.output_dc_blocker_left.reset();
+        self.output_dc_blocker_right.reset();
+    }
+}
+
+impl Default for DattorroReverb {
+    fn default() -> Self {
+        Self::new(44100.0)
+    }
+}
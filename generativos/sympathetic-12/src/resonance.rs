@@ -17,6 +17,19 @@
 //!
 //! A 12×12 matrix where entry [i][j] represents how much string i
 //! excites string j. Values are based on psychoacoustic consonance.
+//!
+//! Coupling is realized as a lossless scattering (Kirchhoff) junction per
+//! target string: every other string's output is an incoming wave on a
+//! port whose admittance is that string's interval weight, the target's
+//! own output is the remaining port, and the junction pressure
+//! `2 * sum(Y_i * a_i) / sum(Y_i)` is passive by construction — it cannot
+//! return more energy than the ports put in. What gets injected back into
+//! each string is how far the reflected/transmitted component
+//! (`pressure - own_output`) departs from the string's own output, i.e.
+//! `pressure - 2 * own_output`, which is zero when no other port is open;
+//! so resonance builds and decays physically and a struck
+//! string can pull energy back out of the sympathetic set, with no
+//! separate safety clamp required.
 
 use crate::NUM_STRINGS;
 
@@ -31,6 +44,10 @@ pub struct SympatheticMatrix {
 
     /// Smoothing coefficient for resonance buildup
     smoothing: f32,
+
+    /// Scales coupling admittances while preserving the junction's
+    /// passivity (the normalization in `process` divides it back out)
+    coupling_gain: f32,
 }
 
 impl SympatheticMatrix {
@@ -67,11 +84,18 @@ impl SympatheticMatrix {
             matrix,
             resonance_buffers: [0.0; NUM_STRINGS],
             smoothing: 0.999, // Slow buildup and decay
+            coupling_gain: 1.0,
         }
     }
 
     /// Process string outputs and return sympathetic excitation for each string
     ///
+    /// Forms a lossless scattering junction per target string out of its
+    /// own output and every other (sufficiently energetic) string's
+    /// output, weighted by the interval-coupling matrix as admittances.
+    /// Because the junction pressure is bounded by the incoming waves,
+    /// this conserves energy instead of relying on a hard clamp.
+    ///
     /// # Arguments
     /// * `string_outputs` - Current output sample from each string
     /// * `string_energies` - Energy level of each string (for gating)
@@ -85,41 +109,52 @@ impl SympatheticMatrix {
         // Energy gate threshold - only couple if source has real energy
         let energy_gate = 0.01;
 
-        // Very small scale - resonance builds up naturally over many cycles
-        // The delay line filters out non-resonant frequencies automatically
-        let scale = amount * 0.002;
+        let gain = amount * self.coupling_gain;
 
-        // For each source string
-        for source in 0..NUM_STRINGS {
-            // Gate: only couple if source is actually vibrating
-            if string_energies[source] < energy_gate {
-                continue;
-            }
+        for target in 0..NUM_STRINGS {
+            // The target's own output is one port of the junction, with
+            // unit admittance, so the junction reduces to "no coupling"
+            // when every other port is gated out.
+            let own_output = string_outputs[target];
+            let mut admittance_sum = 1.0f32;
+            let mut weighted_sum = own_output;
 
-            let source_signal = string_outputs[source];
-
-            // Excite target strings based on coupling matrix
-            for target in 0..NUM_STRINGS {
-                if source != target {
-                    let coupling = self.matrix[source][target];
-                    excitation[target] += source_signal * coupling * scale;
+            for source in 0..NUM_STRINGS {
+                if source == target {
+                    continue;
                 }
+                // Gate: only couple if source is actually vibrating
+                if string_energies[source] < energy_gate {
+                    continue;
+                }
+
+                let admittance = self.matrix[source][target] * gain;
+                admittance_sum += admittance;
+                weighted_sum += admittance * string_outputs[source];
             }
-        }
 
-        // Direct output - no smoothing buffer (the delay line does the filtering)
-        // Just safety clamp
-        for i in 0..NUM_STRINGS {
-            excitation[i] = excitation[i].clamp(-0.1, 0.1);
+            // Parallel scattering junction: the outgoing pressure is
+            // bounded by the incoming waves by construction, so the
+            // reflected/transmitted component can't add energy.
+            let junction_pressure = 2.0 * weighted_sum / admittance_sum;
+            let reflected = junction_pressure - own_output;
+            // The string already carries its own wave; inject only what the
+            // other ports change about it (zero when they are all gated out)
+            let coupled = reflected - own_output;
 
-            if !excitation[i].is_finite() {
-                excitation[i] = 0.0;
-            }
+            excitation[target] = if coupled.is_finite() { coupled } else { 0.0 };
         }
 
         excitation
     }
 
+    /// Set the coupling gain, which scales admittances into the
+    /// scattering junction while the junction's own normalization keeps
+    /// it passive regardless of the gain value
+    pub fn set_coupling_gain(&mut self, gain: f32) {
+        self.coupling_gain = gain.max(0.0);
+    }
+
     /// Get the raw coupling matrix (for visualization)
     pub fn get_matrix(&self) -> Vec<f32> {
         let mut flat = Vec::with_capacity(NUM_STRINGS * NUM_STRINGS);
@@ -129,6 +164,20 @@ impl SympatheticMatrix {
         flat
     }
 
+    /// Replace the entire coupling matrix from a flat, row-major slice
+    /// (the same layout `get_matrix` produces). Ignored if `flat` is not
+    /// exactly `NUM_STRINGS * NUM_STRINGS` entries long.
+    pub fn set_matrix(&mut self, flat: &[f32]) {
+        if flat.len() != NUM_STRINGS * NUM_STRINGS {
+            return;
+        }
+        for source in 0..NUM_STRINGS {
+            for target in 0..NUM_STRINGS {
+                self.matrix[source][target] = flat[source * NUM_STRINGS + target];
+            }
+        }
+    }
+
     /// Set coupling strength for a specific interval
     pub fn set_interval_coupling(&mut self, interval: usize, strength: f32) {
         if interval >= 12 {
@@ -251,4 +300,59 @@ impl SympatheticMatrix {
         self.set_interval_coupling(8, 0.5);
         self.set_interval_coupling(10, 0.5);
     }
+
+    /// Just-intonation coupling: each interval class is weighted by the
+    /// simplicity of the low-integer frequency ratio it approximates
+    /// (unison 1:1, fifth 3:2, fourth 4:3, ...), as `2 / (numerator +
+    /// denominator)`, so simpler ratios couple more strongly. How far the
+    /// equal-tempered interval lies from that ratio is not taken into account.
+    pub fn matrix_just_intonation(&mut self) {
+        // (numerator, denominator) of the just ratio each semitone
+        // interval approximates
+        const JUST_RATIOS: [(u32, u32); 12] = [
+            (1, 1),   // 0: unison
+            (16, 15), // 1: minor second
+            (9, 8),   // 2: major second
+            (6, 5),   // 3: minor third
+            (5, 4),   // 4: major third
+            (4, 3),   // 5: perfect fourth
+            (45, 32),// 6: tritone
+            (3, 2),   // 7: perfect fifth
+            (8, 5),   // 8: minor sixth
+            (5, 3),   // 9: major sixth
+            (9, 5),   // 10: minor seventh
+            (15, 8),  // 11: major seventh
+        ];
+        // Unison (1:1) is the simplest possible ratio, so it normalizes
+        // every other interval's weight to the 0-1 range
+        let unison_complexity = 1.0 / (JUST_RATIOS[0].0 + JUST_RATIOS[0].1) as f32;
+
+        for (interval, &(num, den)) in JUST_RATIOS.iter().enumerate() {
+            let complexity = 1.0 / (num + den) as f32;
+            self.set_interval_coupling(interval, complexity / unison_complexity);
+        }
+    }
+
+    /// Circle-of-fifths coupling: strength falls off with the number of
+    /// fifths needed to reach a given interval class (0 = unison, 1 =
+    /// a fifth/fourth away, up to 6 = the tritone, the far point of
+    /// the circle).
+    pub fn matrix_circle_of_fifths(&mut self) {
+        // Circle-of-fifths distance for each semitone interval class,
+        // derived by walking the circle (0, 7, 2, 9, 4, 11, 6, 1, 8, 3,
+        // 10, 5) and taking the shorter direction around it
+        const FIFTHS_DISTANCE: [u32; 12] = [0, 5, 2, 3, 4, 1, 6, 1, 4, 3, 2, 5];
+
+        for (interval, &distance) in FIFTHS_DISTANCE.iter().enumerate() {
+            self.set_interval_coupling(interval, 1.0 / (1.0 + distance as f32));
+        }
+    }
+
+    /// Uniform coupling: every interval class set to the same strength
+    pub fn matrix_uniform(&mut self, amount: f32) {
+        let amount = amount.clamp(0.0, 1.0);
+        for i in 0..12 {
+            self.set_interval_coupling(i, amount);
+        }
+    }
 }
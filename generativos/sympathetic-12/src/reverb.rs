@@ -8,7 +8,17 @@
 //! This creates a lush, natural-sounding reverb suitable for
 //! acoustic instrument simulation.
 
-use crate::filters::{OnePole, Allpass, Comb};
+use crate::filters::{DelayBuffer, Interpolation, OnePole, Allpass, Comb};
+
+/// Maximum pre-delay, in seconds
+const MAX_PRE_DELAY_SECONDS: f32 = 0.5;
+
+/// Extra delay-line capacity reserved per comb so the modulation LFO can
+/// push the read tap above its base delay without wrapping
+const MOD_HEADROOM_SAMPLES: usize = 64;
+
+/// Largest modulation depth accepted by `set_modulation`, in samples
+const MAX_MOD_DEPTH_SAMPLES: f32 = MOD_HEADROOM_SAMPLES as f32 - 4.0;
 
 /// Number of comb filters per channel
 const NUM_COMBS: usize = 8;
@@ -58,6 +68,41 @@ pub struct FDNReverb {
 
     /// Output gain
     gain: f32,
+
+    /// Whether the tail is currently frozen (infinite sustain)
+    frozen: bool,
+
+    /// Comb feedback from just before freezing, restored on unfreeze
+    pre_freeze_feedback: f32,
+
+    /// Pre-delay ring buffer (runs ahead of the comb/allpass tank)
+    pre_delay_line: DelayBuffer,
+
+    /// Pre-delay time, in samples
+    pre_delay_samples: f32,
+
+    /// Wet (reverb) output level
+    wet: f32,
+
+    /// Dry (input) output level
+    dry: f32,
+
+    /// Sample rate, needed to convert pre-delay time from milliseconds
+    sample_rate: f32,
+
+    /// Unmodulated comb delay times, in samples (left/right)
+    base_delays_left: [f32; NUM_COMBS],
+    base_delays_right: [f32; NUM_COMBS],
+
+    /// Per-comb LFO phase, in radians (left/right)
+    mod_phases_left: [f32; NUM_COMBS],
+    mod_phases_right: [f32; NUM_COMBS],
+
+    /// Modulation depth, in samples (0 = bit-identical to unmodulated output)
+    mod_depth: f32,
+
+    /// Modulation rate, in Hz, before per-comb detuning
+    mod_rate: f32,
 }
 
 /// Allpass filter with longer delay for reverb
@@ -104,23 +149,37 @@ impl FDNReverb {
         // Scale delay times for sample rate
         let scale = sample_rate / 44100.0;
 
-        // Create comb filters
-        let combs_left: Vec<Comb> = COMB_DELAYS_LEFT
+        // Scaled integer delays actually used to build each comb's delay
+        // line; `base_delays_*` below is derived from these same values
+        // (not recomputed) so depth-0 modulation is bit-identical
+        let scaled_delays_left: [usize; NUM_COMBS] =
+            std::array::from_fn(|i| (COMB_DELAYS_LEFT[i] as f32 * scale) as usize);
+        let scaled_delays_right: [usize; NUM_COMBS] =
+            std::array::from_fn(|i| (COMB_DELAYS_RIGHT[i] as f32 * scale) as usize);
+
+        // Create comb filters, with extra delay-line headroom so the
+        // modulation LFO can push the read tap above the base delay
+        let combs_left: Vec<Comb> = scaled_delays_left
             .iter()
-            .map(|&d| {
-                let delay = (d as f32 * scale) as usize;
-                Comb::new(delay, 0.84, 0.2)
-            })
+            .map(|&delay| Comb::with_headroom(delay, 0.84, 0.2, MOD_HEADROOM_SAMPLES))
             .collect();
 
-        let combs_right: Vec<Comb> = COMB_DELAYS_RIGHT
+        let combs_right: Vec<Comb> = scaled_delays_right
             .iter()
-            .map(|&d| {
-                let delay = (d as f32 * scale) as usize;
-                Comb::new(delay, 0.84, 0.2)
-            })
+            .map(|&delay| Comb::with_headroom(delay, 0.84, 0.2, MOD_HEADROOM_SAMPLES))
             .collect();
 
+        let base_delays_left: [f32; NUM_COMBS] = std::array::from_fn(|i| scaled_delays_left[i] as f32);
+        let base_delays_right: [f32; NUM_COMBS] = std::array::from_fn(|i| scaled_delays_right[i] as f32);
+
+        // Detune each comb's LFO phase and rate slightly so the combs
+        // don't all wobble in lockstep (which would sound like a single
+        // pitch modulation instead of a diffuse chorus)
+        let mod_phases_left: [f32; NUM_COMBS] =
+            std::array::from_fn(|i| (i as f32 / NUM_COMBS as f32) * std::f32::consts::TAU);
+        let mod_phases_right: [f32; NUM_COMBS] =
+            std::array::from_fn(|i| ((i as f32 + 0.5) / NUM_COMBS as f32) * std::f32::consts::TAU);
+
         // Create allpass filters
         let allpasses_left: Vec<ReverbAllpass> = ALLPASS_DELAYS_LEFT
             .iter()
@@ -148,23 +207,64 @@ impl FDNReverb {
             damping: 0.5,
             width: 1.0,
             gain: 0.015, // Reverb is added to dry signal, so keep low
+            frozen: false,
+            pre_freeze_feedback: 0.7 + 0.5 * 0.28,
+            pre_delay_line: DelayBuffer::new(
+                (MAX_PRE_DELAY_SECONDS * sample_rate) as usize + 1,
+                Interpolation::Linear,
+            ),
+            pre_delay_samples: 0.0,
+            wet: 1.0,
+            dry: 0.0,
+            sample_rate,
+            base_delays_left,
+            base_delays_right,
+            mod_phases_left,
+            mod_phases_right,
+            mod_depth: 0.0,
+            mod_rate: 0.0,
         }
     }
 
     /// Process one mono input sample and return stereo output
+    ///
+    /// The returned pair is a proper `dry*input + wet*reverb` mix, so
+    /// callers don't need to sum the dry signal themselves.
     pub fn process(&mut self, input: f32) -> (f32, f32) {
-        // Input filtering
-        let filtered_input = self.input_lowpass.process(input);
-
-        // Process comb filters in parallel
+        let pre_delayed = self.pre_delay_line.read_frac(self.pre_delay_samples);
+        self.pre_delay_line.write(input);
+
+        // While frozen, the comb bank must not hear new input at all (not
+        // even through the lowpass) or the frozen tail would pick up new
+        // material instead of looping forever unchanged
+        let filtered_input = if self.frozen {
+            0.0
+        } else {
+            self.input_lowpass.process(pre_delayed)
+        };
+
+        // Process comb filters in parallel, modulating each comb's read
+        // tap with its own (slightly detuned) LFO. At depth 0 this adds
+        // exactly 0 to the base delay, so the output is bit-identical to
+        // the unmodulated path.
         let mut left_sum = 0.0;
         let mut right_sum = 0.0;
 
-        for comb in &mut self.combs_left {
+        let phase_step = std::f32::consts::TAU * self.mod_rate / self.sample_rate;
+
+        for (i, comb) in self.combs_left.iter_mut().enumerate() {
+            let lfo = self.mod_phases_left[i].sin();
+            comb.set_delay_frac(self.base_delays_left[i] + lfo * self.mod_depth);
+            self.mod_phases_left[i] =
+                (self.mod_phases_left[i] + phase_step * (1.0 + i as f32 * 0.071)) % std::f32::consts::TAU;
             left_sum += comb.process(filtered_input);
         }
 
-        for comb in &mut self.combs_right {
+        for (i, comb) in self.combs_right.iter_mut().enumerate() {
+            let lfo = self.mod_phases_right[i].sin();
+            comb.set_delay_frac(self.base_delays_right[i] + lfo * self.mod_depth);
+            self.mod_phases_right[i] =
+                (self.mod_phases_right[i] + phase_step * (1.0 + i as f32 * 0.089)) % std::f32::consts::TAU;
             right_sum += comb.process(filtered_input);
         }
 
@@ -187,8 +287,8 @@ impl FDNReverb {
         let mono = (left_out + right_out) * 0.5;
         let stereo = (left_out - right_out) * 0.5;
 
-        let mut final_left = (mono + stereo * self.width) * self.gain;
-        let mut final_right = (mono - stereo * self.width) * self.gain;
+        let mut final_left = (mono + stereo * self.width) * self.gain * self.wet + input * self.dry;
+        let mut final_right = (mono - stereo * self.width) * self.gain * self.wet + input * self.dry;
 
         // Safety clamp and NaN protection
         if !final_left.is_finite() { final_left = 0.0; }
@@ -197,6 +297,11 @@ impl FDNReverb {
         (final_left.clamp(-1.0, 1.0), final_right.clamp(-1.0, 1.0))
     }
 
+    /// Get current room size setting (0-1)
+    pub fn get_room_size(&self) -> f32 {
+        self.room_size
+    }
+
     /// Set room size (0-1)
     pub fn set_room_size(&mut self, size: f32) {
         self.room_size = size.clamp(0.0, 1.0);
@@ -204,15 +309,47 @@ impl FDNReverb {
         // Room size affects comb feedback
         // Larger room = longer decay = higher feedback
         let feedback = 0.7 + self.room_size * 0.28;
+        self.pre_freeze_feedback = feedback;
+
+        // Don't fight the freeze: while frozen, feedback stays at 1.0 and
+        // the room-size-derived value is restored on unfreeze instead
+        if !self.frozen {
+            for comb in &mut self.combs_left {
+                comb.set_feedback(feedback);
+            }
+            for comb in &mut self.combs_right {
+                comb.set_feedback(feedback);
+            }
+        }
+    }
+
+    /// Freeze or unfreeze the tail (infinite sustain)
+    ///
+    /// While frozen, comb feedback is pushed to 1.0 (lossless) and the
+    /// combs stop hearing new input, so whatever is currently in the tail
+    /// loops forever. Unfreezing restores the room-size-derived feedback
+    /// and lets new input back in, glitch-free since the pre-freeze
+    /// feedback is remembered rather than recomputed.
+    pub fn set_freeze(&mut self, freeze: bool) {
+        if freeze == self.frozen {
+            return;
+        }
+        self.frozen = freeze;
 
+        let feedback = if freeze { 1.0 } else { self.pre_freeze_feedback };
         for comb in &mut self.combs_left {
-            comb.set_feedback(feedback);
+            comb.set_feedback_raw(feedback);
         }
         for comb in &mut self.combs_right {
-            comb.set_feedback(feedback);
+            comb.set_feedback_raw(feedback);
         }
     }
 
+    /// Get current damping setting (0-1)
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
     /// Set damping (0-1)
     pub fn set_damping(&mut self, damping: f32) {
         self.damping = damping.clamp(0.0, 1.0);
@@ -236,6 +373,37 @@ impl FDNReverb {
         self.gain = gain.clamp(0.0, 1.0);
     }
 
+    /// Set the wet (reverb) output level (0-1)
+    pub fn set_wet(&mut self, wet: f32) {
+        self.wet = wet.clamp(0.0, 1.0);
+    }
+
+    /// Set the dry (input) output level (0-1)
+    pub fn set_dry(&mut self, dry: f32) {
+        self.dry = dry.clamp(0.0, 1.0);
+    }
+
+    /// Set the comb-modulation LFO depth (in samples) and rate (in Hz)
+    ///
+    /// Each comb wobbles around its base delay with its own detuned
+    /// phase/rate, chorusing the tail and reducing metallic ringing on
+    /// sustained tones. At `depth` 0 the output is bit-identical to the
+    /// unmodulated path.
+    pub fn set_modulation(&mut self, depth: f32, rate: f32) {
+        self.mod_depth = depth.clamp(0.0, MAX_MOD_DEPTH_SAMPLES);
+        self.mod_rate = rate.max(0.0);
+    }
+
+    /// Set pre-delay time in milliseconds (0 to `MAX_PRE_DELAY_SECONDS * 1000`).
+    /// At 0 (the default), `pre_delay_line.read_frac(0.0)` returns the
+    /// sample written on the previous call, so pre-delay is effectively
+    /// off (aside from the line's inherent single-sample pipeline delay).
+    pub fn set_pre_delay_ms(&mut self, ms: f32) {
+        let max_ms = MAX_PRE_DELAY_SECONDS * 1000.0;
+        let samples = (ms.clamp(0.0, max_ms) / 1000.0) * self.sample_rate;
+        self.pre_delay_samples = samples;
+    }
+
     /// Clear all buffers
     pub fn clear(&mut self) {
         for comb in &mut self.combs_left {
@@ -251,6 +419,7 @@ impl FDNReverb {
             allpass.clear();
         }
         self.input_lowpass.reset();
+        self.pre_delay_line.clear();
     }
 }
 
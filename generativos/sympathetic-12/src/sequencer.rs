@@ -0,0 +1,156 @@
+//! Sample-accurate internal tempo clock and event scheduler
+//!
+//! Lets a host schedule plucks and chords in beats instead of driving
+//! every `pluck` call itself: `Sequencer` converts beat positions to
+//! absolute sample positions via `set_tempo`, queues them in a min-heap
+//! keyed by that sample position, and `tick` (called once per sample
+//! inside `Sympathetic12::process`) fires whatever has just arrived.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// An action fired by the sequencer when its scheduled sample time arrives
+#[derive(Clone, Debug)]
+pub enum ScheduledAction {
+    /// Pluck a single string
+    Pluck { pitch_class: usize, velocity: f32, position: f32 },
+    /// Pluck a Forte prime-form chord
+    PrimeForm { prime_form: Vec<u8>, transposition: u8, velocity: f32 },
+    /// Damp a single string
+    Damp { pitch_class: usize, amount: f32 },
+}
+
+#[derive(Clone)]
+struct ScheduledEvent {
+    sample_time: u64,
+    action: ScheduledAction,
+}
+
+impl PartialEq for ScheduledEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.sample_time == other.sample_time
+    }
+}
+impl Eq for ScheduledEvent {}
+impl PartialOrd for ScheduledEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ScheduledEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap by default) behaves as a
+        // min-heap keyed by sample time
+        other.sample_time.cmp(&self.sample_time)
+    }
+}
+
+/// Sample-accurate tempo-clocked event scheduler
+pub struct Sequencer {
+    /// Sample rate, for converting beats to samples
+    sample_rate: f32,
+    /// Current tempo in beats per minute
+    bpm: f32,
+    /// Global sample counter, advanced once per `tick`
+    sample_counter: u64,
+    /// Loop length in samples, if looping is enabled
+    loop_length_samples: Option<u64>,
+    /// Events due to fire, ordered by sample time (min-heap)
+    heap: BinaryHeap<ScheduledEvent>,
+    /// Original schedule, replayed into `heap` each time the counter
+    /// wraps so a loop repeats the same material
+    template: Vec<ScheduledEvent>,
+}
+
+impl Sequencer {
+    /// Create a new sequencer at the given sample rate (120 BPM, no loop)
+    pub fn new(sample_rate: f32) -> Self {
+        Sequencer {
+            sample_rate,
+            bpm: 120.0,
+            sample_counter: 0,
+            loop_length_samples: None,
+            heap: BinaryHeap::new(),
+            template: Vec::new(),
+        }
+    }
+
+    /// Set tempo in beats per minute
+    pub fn set_tempo(&mut self, bpm: f32) {
+        self.bpm = bpm.max(1.0);
+    }
+
+    /// Convert a beat position to an absolute sample position at the
+    /// current tempo
+    fn beats_to_samples(&self, beat: f64) -> u64 {
+        ((beat.max(0.0) * 60.0 / self.bpm as f64) * self.sample_rate as f64) as u64
+    }
+
+    /// Schedule a single-string pluck at `beat`
+    pub fn schedule_pluck(&mut self, beat: f64, pitch_class: usize, velocity: f32, position: f32) {
+        let event = ScheduledEvent {
+            sample_time: self.beats_to_samples(beat),
+            action: ScheduledAction::Pluck { pitch_class, velocity, position },
+        };
+        self.heap.push(event.clone());
+        self.template.push(event);
+    }
+
+    /// Schedule a Forte prime-form chord at `beat`
+    pub fn schedule_prime_form(&mut self, beat: f64, prime_form: Vec<u8>, transposition: u8, velocity: f32) {
+        let event = ScheduledEvent {
+            sample_time: self.beats_to_samples(beat),
+            action: ScheduledAction::PrimeForm { prime_form, transposition, velocity },
+        };
+        self.heap.push(event.clone());
+        self.template.push(event);
+    }
+
+    /// Schedule a damp of a single string at `beat`
+    pub fn schedule_damp(&mut self, beat: f64, pitch_class: usize, amount: f32) {
+        let event = ScheduledEvent {
+            sample_time: self.beats_to_samples(beat),
+            action: ScheduledAction::Damp { pitch_class, amount },
+        };
+        self.heap.push(event.clone());
+        self.template.push(event);
+    }
+
+    /// Clear all scheduled events and reset the sample counter
+    pub fn clear_schedule(&mut self) {
+        self.heap.clear();
+        self.template.clear();
+        self.sample_counter = 0;
+    }
+
+    /// Set the loop length in beats; the sample counter wraps to 0 once
+    /// it passes this point and the original schedule fires again. Pass
+    /// 0 (or a negative value) to disable looping.
+    pub fn set_loop_length_beats(&mut self, beats: f64) {
+        self.loop_length_samples = if beats > 0.0 { Some(self.beats_to_samples(beats)) } else { None };
+    }
+
+    /// Advance the sample counter by one sample and return every action
+    /// whose scheduled time has just arrived
+    pub fn tick(&mut self) -> Vec<ScheduledAction> {
+        let mut fired = Vec::new();
+
+        while let Some(event) = self.heap.peek() {
+            if event.sample_time > self.sample_counter {
+                break;
+            }
+            fired.push(self.heap.pop().expect("heap.peek() just confirmed an event").action);
+        }
+
+        self.sample_counter += 1;
+
+        if let Some(loop_len) = self.loop_length_samples {
+            if self.sample_counter >= loop_len {
+                self.sample_counter = 0;
+                self.heap = self.template.iter().cloned().collect();
+            }
+        }
+
+        fired
+    }
+}
@@ -10,13 +10,60 @@
 //! - Variable damping and brightness controls
 //! - Pluck position simulation
 //! - Inharmonicity for bell-like tones
+//! - Reed/bow driven excitation as an alternative to plucking
+//! - Selectable chaotic pluck excitation sources (in `excitation`)
+//! - Pitch glide, vibrato, and exponential FM, all continuously
+//!   retuning through the same fractional-delay allpass interpolation
 
-use crate::filters::{OnePole, Allpass, DCBlocker};
+use crate::excitation::{BowModel, ChaosGenerator, ExcitationMode, ExcitationSource, ReedModel};
+use crate::filters::{fast_sin, Allpass, DCBlocker, OnePole, StateVariable};
 use crate::SAMPLE_RATE;
 
 /// Maximum delay line length (supports frequencies down to ~20 Hz)
 const MAX_DELAY_LENGTH: usize = 4096;
 
+/// Lowest/highest cutoff `set_brightness` maps to when the string is in
+/// `DampingMode::Svf`, in Hz
+const SVF_BRIGHTNESS_MIN_HZ: f32 = 400.0;
+const SVF_BRIGHTNESS_MAX_HZ: f32 = 8000.0;
+
+/// Which filter topology shapes the damping (brightness) path in the
+/// feedback loop
+enum DampingMode {
+    /// Original one-pole lowpass, driven directly by a coefficient
+    OnePole(OnePole),
+    /// TPT state-variable filter (lowpass output), stays stable when
+    /// brightness is swept at audio rate and pushed close to Nyquist -
+    /// useful for continuously-driven reed/bow excitation rather than a
+    /// one-shot pluck's fixed decay
+    Svf(StateVariable),
+}
+
+impl DampingMode {
+    fn process(&mut self, input: f32) -> f32 {
+        match self {
+            DampingMode::OnePole(filter) => filter.process(input),
+            DampingMode::Svf(filter) => filter.process(input).lowpass,
+        }
+    }
+
+    /// Apply a brightness value (0-1) using whichever mapping the active
+    /// topology needs
+    fn set_brightness(&mut self, brightness: f32) {
+        match self {
+            DampingMode::OnePole(filter) => {
+                let cutoff = 0.2 + brightness * 0.6; // Range: 0.2 to 0.8
+                filter.set_coefficient(cutoff);
+            }
+            DampingMode::Svf(filter) => {
+                let cutoff_hz =
+                    SVF_BRIGHTNESS_MIN_HZ + brightness * (SVF_BRIGHTNESS_MAX_HZ - SVF_BRIGHTNESS_MIN_HZ);
+                filter.set_cutoff(cutoff_hz);
+            }
+        }
+    }
+}
+
 /// Karplus-Strong string model
 pub struct KarplusStrong {
     /// Circular delay line buffer
@@ -31,14 +78,73 @@ pub struct KarplusStrong {
     /// Fractional delay for precise tuning
     fractional_delay: f32,
 
-    /// Current frequency in Hz
+    /// Current frequency in Hz (may be drifting away from
+    /// `target_frequency` while detune modulation is active)
     frequency: f32,
 
+    /// Frequency as explicitly set via `set_frequency` or
+    /// `set_target_frequency`, independent of
+    /// any detune modulation currently being applied on top of it
+    target_frequency: f32,
+
+    /// Detune modulation depth, as a fraction of `target_frequency`
+    /// (0 = off)
+    detune_depth: f32,
+
+    /// Random-walk update rate in Hz
+    detune_rate: f32,
+
+    /// Current interpolated random-walk value, in `[-1, 1]`
+    detune_walk_value: f32,
+
+    /// Random-walk target the value is currently sliding towards
+    detune_walk_target: f32,
+
+    /// Per-sample increment towards `detune_walk_target`
+    detune_step: f32,
+
+    /// Samples remaining before a new random-walk target is picked
+    detune_samples_remaining: u32,
+
+    /// Samples between random-walk target updates, derived from
+    /// `detune_rate`
+    detune_interval_samples: u32,
+
+    /// Frequency the glide is currently sliding towards
+    glide_target_freq: f32,
+
+    /// Current glide position, interpolated in log-frequency space so
+    /// the slide sounds like a constant-rate pitch ramp
+    glide_current_freq: f32,
+
+    /// Per-sample multiplicative step (as a natural-log increment)
+    /// towards `glide_target_freq`
+    glide_log_increment: f32,
+
+    /// Samples remaining in the current glide
+    glide_remaining: u32,
+
+    /// Vibrato LFO depth, in cents (0 disables it)
+    vibrato_depth_cents: f32,
+
+    /// Vibrato LFO phase increment per sample
+    vibrato_phase_step: f32,
+
+    /// Vibrato LFO phase, in radians
+    vibrato_phase: f32,
+
+    /// External exponential-FM input, updated via `set_fm_input`
+    fm_input: f32,
+
+    /// How many octaves of pitch deviation a full-scale `fm_input` (1.0)
+    /// produces
+    fm_depth_octaves: f32,
+
     /// Feedback coefficient (affects sustain)
     feedback: f32,
 
     /// Damping filter (lowpass in feedback)
-    damping_filter: OnePole,
+    damping_filter: DampingMode,
 
     /// Allpass filter for fractional delay interpolation
     allpass: Allpass,
@@ -61,8 +167,17 @@ pub struct KarplusStrong {
     /// Energy decay rate for visualization
     energy_decay: f32,
 
-    /// Noise generator state (simple LCG)
+    /// Pluck excitation generator (white noise or a chaotic map)
+    excitation_source: ChaosGenerator,
+
+    /// Noise generator state for the detune random walk (simple LCG,
+    /// independent of `excitation_source` so switching the pluck's
+    /// excitation source doesn't affect detuning)
     noise_state: u32,
+
+    /// Current excitation source: one-shot pluck, or continuously driven
+    /// reed/bow
+    excitation_mode: ExcitationMode,
 }
 
 impl KarplusStrong {
@@ -74,8 +189,25 @@ impl KarplusStrong {
             delay_length: 0,
             fractional_delay: 0.0,
             frequency,
+            target_frequency: frequency,
+            detune_depth: 0.0,
+            detune_rate: 4.0,
+            detune_walk_value: 0.0,
+            detune_walk_target: 0.0,
+            detune_step: 0.0,
+            detune_samples_remaining: 0,
+            detune_interval_samples: (sample_rate / 4.0).max(1.0) as u32,
+            glide_target_freq: frequency,
+            glide_current_freq: frequency,
+            glide_log_increment: 0.0,
+            glide_remaining: 0,
+            vibrato_depth_cents: 0.0,
+            vibrato_phase_step: 0.0,
+            vibrato_phase: 0.0,
+            fm_input: 0.0,
+            fm_depth_octaves: 1.0,
             feedback: 0.998,
-            damping_filter: OnePole::new(0.5),
+            damping_filter: DampingMode::OnePole(OnePole::new(0.5)),
             allpass: Allpass::new(0.5),
             dc_blocker: DCBlocker::new(10.0, sample_rate),
             brightness: 0.5,
@@ -83,15 +215,79 @@ impl KarplusStrong {
             inharmonicity: 0.0,
             energy: 0.0,
             energy_decay: 0.9995,
+            excitation_source: ChaosGenerator::new(),
             noise_state: 12345,
+            excitation_mode: ExcitationMode::Pluck,
         };
         string.set_frequency(frequency);
         string
     }
 
-    /// Set the string frequency
+    /// Set the string's nominal frequency immediately (no glide, and
+    /// cancels any glide in progress)
     pub fn set_frequency(&mut self, frequency: f32) {
-        self.frequency = frequency.max(20.0).min(SAMPLE_RATE / 2.0);
+        self.target_frequency = frequency.max(20.0).min(SAMPLE_RATE / 2.0);
+        self.glide_target_freq = self.target_frequency;
+        self.glide_current_freq = self.target_frequency;
+        self.glide_remaining = 0;
+        self.retune(self.target_frequency);
+    }
+
+    /// Glide smoothly to a new nominal frequency over `glide_ms`
+    /// milliseconds, ramping the effective delay length sample-by-sample
+    /// instead of jumping. A `glide_ms` of 0 (or less) retunes instantly,
+    /// like `set_frequency`.
+    pub fn set_target_frequency(&mut self, frequency: f32, glide_ms: f32) {
+        let frequency = frequency.max(20.0).min(SAMPLE_RATE / 2.0);
+        self.target_frequency = frequency;
+        self.glide_target_freq = frequency;
+
+        if glide_ms <= 0.0 {
+            self.glide_current_freq = frequency;
+            self.glide_remaining = 0;
+            self.retune(frequency);
+        } else {
+            let steps = ((glide_ms / 1000.0) * SAMPLE_RATE).max(1.0);
+            self.glide_log_increment = (frequency.ln() - self.glide_current_freq.ln()) / steps;
+            self.glide_remaining = steps as u32;
+        }
+    }
+
+    /// Set the built-in vibrato LFO: `rate_hz` cycles per second,
+    /// `depth_cents` peak deviation in cents (0 disables it)
+    pub fn set_vibrato(&mut self, rate_hz: f32, depth_cents: f32) {
+        self.vibrato_depth_cents = depth_cents.max(0.0);
+        self.vibrato_phase_step = std::f32::consts::TAU * rate_hz.max(0.0) / SAMPLE_RATE;
+    }
+
+    /// Set how many octaves of pitch deviation a full-scale (1.0)
+    /// `set_fm_input` signal produces
+    pub fn set_fm_depth(&mut self, octaves: f32) {
+        self.fm_depth_octaves = octaves.max(0.0);
+    }
+
+    /// Drive the exponential FM input; an external modulator signal
+    /// (typically -1..1) that bends pitch by `fm_depth_octaves` per unit
+    pub fn set_fm_input(&mut self, signal: f32) {
+        self.fm_input = signal;
+    }
+
+    /// Get the nominal frequency, as last passed to `set_frequency` or
+    /// `set_target_frequency`
+    /// (unaffected by any detune modulation currently applied on top)
+    pub fn get_frequency(&self) -> f32 {
+        self.target_frequency
+    }
+
+    /// Recompute the delay line length and fractional-delay allpass
+    /// coefficient for `frequency`, without touching `target_frequency`.
+    /// Called every sample while detune modulation is active, which is
+    /// safe to do continuously: the allpass interpolation that already
+    /// exists for precise tuning absorbs the sub-sample length change
+    /// each call, so a slowly drifting frequency doesn't click the way
+    /// an abrupt integer delay-length jump would.
+    fn retune(&mut self, frequency: f32) {
+        self.frequency = frequency;
 
         // Calculate delay length
         let total_delay = SAMPLE_RATE / self.frequency;
@@ -113,9 +309,88 @@ impl KarplusStrong {
         self.allpass.set_coefficient(coef);
     }
 
-    /// Get current frequency
-    pub fn get_frequency(&self) -> f32 {
-        self.frequency
+    /// Set the depth of the per-string detune random walk, as a
+    /// fraction of the nominal frequency (0 disables it)
+    pub fn set_detune_depth(&mut self, depth: f32) {
+        self.detune_depth = depth.clamp(0.0, 0.05);
+    }
+
+    /// Set the rate, in Hz, at which the detune random walk picks a new
+    /// target
+    pub fn set_detune_rate(&mut self, hz: f32) {
+        self.detune_rate = hz.clamp(0.05, 20.0);
+        self.detune_interval_samples = (SAMPLE_RATE / self.detune_rate).max(1.0) as u32;
+    }
+
+    /// Advance the detune random walk's interpolated value by one
+    /// sample (does not retune by itself; folded into
+    /// `advance_pitch_modulation` alongside glide/vibrato/FM)
+    fn advance_detune_walk(&mut self) {
+        if self.detune_samples_remaining == 0 {
+            self.detune_walk_target = self.next_noise();
+            self.detune_step =
+                (self.detune_walk_target - self.detune_walk_value) / self.detune_interval_samples as f32;
+            self.detune_samples_remaining = self.detune_interval_samples;
+        }
+        self.detune_walk_value += self.detune_step;
+        self.detune_samples_remaining -= 1;
+    }
+
+    /// Advance every pitch modulator by one sample (glide, detune random
+    /// walk, vibrato LFO, exponential FM) and retune the string to their
+    /// combined result. All of these continuously nudge the same
+    /// fractional-delay-interpolated `retune`, so none of them click.
+    fn advance_pitch_modulation(&mut self) {
+        let gliding = self.glide_remaining > 0;
+        let detuning = self.detune_depth > 0.0;
+        let vibrato_on = self.vibrato_depth_cents > 0.0;
+        let fm_on = self.fm_input != 0.0;
+
+        if !gliding && !detuning && !vibrato_on && !fm_on {
+            return;
+        }
+
+        if gliding {
+            self.glide_current_freq *= self.glide_log_increment.exp();
+            self.glide_remaining -= 1;
+            if self.glide_remaining == 0 {
+                self.glide_current_freq = self.glide_target_freq;
+            }
+        }
+
+        if detuning {
+            self.advance_detune_walk();
+        }
+
+        if vibrato_on {
+            self.vibrato_phase = (self.vibrato_phase + self.vibrato_phase_step) % std::f32::consts::TAU;
+        }
+
+        let detune_factor = 1.0 + self.detune_walk_value * self.detune_depth;
+        let vibrato_factor = if vibrato_on {
+            2f32.powf(self.vibrato_depth_cents / 1200.0 * self.vibrato_phase.sin())
+        } else {
+            1.0
+        };
+        let fm_factor = if fm_on { 2f32.powf(self.fm_input * self.fm_depth_octaves) } else { 1.0 };
+
+        let effective = self.glide_current_freq * detune_factor * vibrato_factor * fm_factor;
+        self.retune(effective);
+    }
+
+    /// Get current damping setting (0-1)
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Get current brightness setting (0-1)
+    pub fn get_brightness(&self) -> f32 {
+        self.brightness
+    }
+
+    /// Get current inharmonicity setting
+    pub fn get_inharmonicity(&self) -> f32 {
+        self.inharmonicity
     }
 
     /// Set damping (0-1, higher = longer sustain)
@@ -127,9 +402,24 @@ impl KarplusStrong {
     /// Set brightness (0-1)
     pub fn set_brightness(&mut self, brightness: f32) {
         self.brightness = brightness.clamp(0.0, 1.0);
-        // Adjust damping filter cutoff based on brightness
-        let cutoff = 0.2 + brightness * 0.6; // Range: 0.2 to 0.8
-        self.damping_filter.set_coefficient(cutoff);
+        self.damping_filter.set_brightness(self.brightness);
+    }
+
+    /// Switch the feedback-loop damping filter between the original
+    /// one-pole lowpass and a TPT state-variable filter. The SVF stays
+    /// stable under continuous audio-rate brightness modulation (e.g. a
+    /// host sweeping brightness while a reed/bow excitation is sustaining
+    /// the note), which the one-pole topology isn't guaranteed to.
+    pub fn set_svf_damping(&mut self, enabled: bool) {
+        self.damping_filter = if enabled {
+            let cutoff_hz =
+                SVF_BRIGHTNESS_MIN_HZ + self.brightness * (SVF_BRIGHTNESS_MAX_HZ - SVF_BRIGHTNESS_MIN_HZ);
+            DampingMode::Svf(StateVariable::new(cutoff_hz, 0.707, SAMPLE_RATE))
+        } else {
+            let mut filter = OnePole::new(0.5);
+            filter.set_coefficient(0.2 + self.brightness * 0.6);
+            DampingMode::OnePole(filter)
+        };
     }
 
     /// Set inharmonicity (for bell-like tones)
@@ -137,6 +427,51 @@ impl KarplusStrong {
         self.inharmonicity = inharmonicity.clamp(0.0, 0.1);
     }
 
+    /// Select which generator feeds the next `pluck`'s excitation noise
+    pub fn set_excitation_source(&mut self, source: ExcitationSource) {
+        self.excitation_source.set_source(source);
+    }
+
+    /// Switch to a blown (reed) excitation, sustaining the note for as
+    /// long as `pressure` stays above zero instead of decaying from a
+    /// single pluck
+    pub fn set_reed_excitation(&mut self, pressure: f32) {
+        let mut reed = ReedModel::new();
+        reed.pressure = pressure.clamp(0.0, 1.0);
+        self.excitation_mode = ExcitationMode::Reed(reed);
+    }
+
+    /// Switch to a bowed excitation, sustaining the note for as long as
+    /// `velocity`/`force` stay above zero
+    pub fn set_bow_excitation(&mut self, velocity: f32, force: f32) {
+        let mut bow = BowModel::new();
+        bow.velocity = velocity;
+        bow.force = force.clamp(0.0, 1.0);
+        self.excitation_mode = ExcitationMode::Bow(bow);
+    }
+
+    /// Update the driving pressure of a reed excitation (no-op if the
+    /// string isn't currently in reed mode)
+    pub fn set_reed_pressure(&mut self, pressure: f32) {
+        if let ExcitationMode::Reed(reed) = &mut self.excitation_mode {
+            reed.pressure = pressure.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Update the bow velocity/force of a bowed excitation (no-op if the
+    /// string isn't currently in bow mode)
+    pub fn set_bow_drive(&mut self, velocity: f32, force: f32) {
+        if let ExcitationMode::Bow(bow) = &mut self.excitation_mode {
+            bow.velocity = velocity;
+            bow.force = force.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Switch back to one-shot plucked excitation
+    pub fn set_pluck_excitation(&mut self) {
+        self.excitation_mode = ExcitationMode::Pluck;
+    }
+
     /// Pluck the string with given velocity and position
     ///
     /// - velocity: 0-1, affects amplitude and brightness
@@ -150,7 +485,7 @@ impl KarplusStrong {
 
         // Fill with noise scaled by velocity (higher amplitude for richer sound)
         for i in 0..self.delay_length {
-            excitation[i] = self.next_noise() * velocity;
+            excitation[i] = self.excitation_source.next() * velocity;
         }
 
         // === Primary comb filter (pluck position) ===
@@ -200,11 +535,11 @@ impl KarplusStrong {
             excitation[i] *= env;
             // Add bright transient for pluck attack
             if i < attack_samples / 2 {
-                excitation[i] += self.next_noise() * velocity * 0.55 * (1.0 - env);
+                excitation[i] += self.excitation_source.next() * velocity * 0.55 * (1.0 - env);
             }
             // Add subtle harmonic "ping" at attack
             if i < attack_samples / 3 {
-                let ping = (i as f32 * 0.5).sin() * velocity * 0.25;
+                let ping = fast_sin(i as f32 * 0.5) * velocity * 0.25;
                 excitation[i] += ping * (1.0 - env);
             }
         }
@@ -237,6 +572,8 @@ impl KarplusStrong {
     /// External excitation is used for sympathetic resonance
     #[inline]
     pub fn process(&mut self, excitation: f32) -> f32 {
+        self.advance_pitch_modulation();
+
         // Read from delay line with fractional interpolation
         let read_pos = (self.write_pos + MAX_DELAY_LENGTH - self.delay_length) % MAX_DELAY_LENGTH;
         let sample = self.delay_line[read_pos];
@@ -252,21 +589,39 @@ impl KarplusStrong {
 
         // Add inharmonicity (slight pitch variation for bell-like tones)
         let inharmonic = if self.inharmonicity > 0.0 {
-            let offset = ((self.write_pos as f32 * self.inharmonicity * 0.1).sin() * 2.0) as i32;
+            let offset = (fast_sin(self.write_pos as f32 * self.inharmonicity * 0.1) * 2.0) as i32;
             let alt_pos = (read_pos as i32 + offset).rem_euclid(MAX_DELAY_LENGTH as i32) as usize;
             feedback_sample * (1.0 - self.inharmonicity) + self.delay_line[alt_pos] * self.inharmonicity
         } else {
             feedback_sample
         };
 
+        // Driven (reed/bow) excitation sustains the note instead of only
+        // letting the pluck decay; it reads the current bore/string sample
+        // back out of the junction, so it has to run after we know `sample`
+        let driven = match &mut self.excitation_mode {
+            ExcitationMode::Pluck => 0.0,
+            ExcitationMode::Reed(reed) => reed.excite(sample),
+            ExcitationMode::Bow(bow) => {
+                let bow_tap = (self.delay_length as f32 * bow.position) as usize;
+                let tap_offset = bow_tap.clamp(1, self.delay_length.saturating_sub(1).max(1));
+                let tap_a_pos = (read_pos + MAX_DELAY_LENGTH - tap_offset) % MAX_DELAY_LENGTH;
+                let tap_b_pos = (tap_a_pos + MAX_DELAY_LENGTH - 1) % MAX_DELAY_LENGTH;
+                bow.excite(self.delay_line[tap_a_pos], self.delay_line[tap_b_pos])
+            }
+        };
+
         // Write back to delay line with external excitation
-        self.delay_line[self.write_pos] = inharmonic + excitation;
+        self.delay_line[self.write_pos] = inharmonic + excitation + driven;
 
         // Advance write position
         self.write_pos = (self.write_pos + 1) % MAX_DELAY_LENGTH;
 
         // Update energy (exponential decay tracking)
         self.energy = (self.energy * self.energy_decay).max(sample.abs());
+        if !matches!(self.excitation_mode, ExcitationMode::Pluck) {
+            self.energy = self.energy.max(driven.abs());
+        }
 
         // DC blocking
         self.dc_blocker.process(interpolated)
@@ -0,0 +1,82 @@
+//! Sympathetic resonance bank: driven + passive resonator strings
+//!
+//! `KarplusStrong::process` already accepts an external `excitation`
+//! argument "for sympathetic resonance", but nothing drives it on its
+//! own; `SympatheticBank` closes that loop for a flat pool of strings
+//! (rather than the fixed 12-pitch-class matrix in `resonance`): every
+//! sample, each string is fed `coupling_gain * (sum of every other
+//! string's last output)`, so energy from a plucked string bleeds into
+//! the rest of the bank and rings them sympathetically, the way open
+//! sitar or piano strings pick up a played note.
+
+use crate::string::KarplusStrong;
+use crate::SAMPLE_RATE;
+
+/// A flat pool of coupled resonator strings
+pub struct SympatheticBank {
+    strings: Vec<KarplusStrong>,
+    last_outputs: Vec<f32>,
+    coupling_gain: f32,
+}
+
+impl SympatheticBank {
+    /// Create an empty bank (add resonators with `add_resonator`)
+    pub fn new() -> Self {
+        SympatheticBank {
+            strings: Vec::new(),
+            last_outputs: Vec::new(),
+            coupling_gain: 0.15,
+        }
+    }
+
+    /// Add a resonator string tuned to `freq`, returning its index
+    pub fn add_resonator(&mut self, freq: f32) -> usize {
+        self.strings.push(KarplusStrong::new(freq, SAMPLE_RATE));
+        self.last_outputs.push(0.0);
+        self.strings.len() - 1
+    }
+
+    /// Set the overall sympathetic coupling gain between strings
+    pub fn set_coupling(&mut self, gain: f32) {
+        self.coupling_gain = gain.max(0.0);
+    }
+
+    /// Directly pluck the resonator at `index` (driving it, rather than
+    /// only exciting it through sympathetic coupling)
+    pub fn pluck(&mut self, index: usize, velocity: f32, position: f32) {
+        if let Some(string) = self.strings.get_mut(index) {
+            string.pluck(velocity, position);
+        }
+    }
+
+    /// Number of resonators currently in the bank
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// True if the bank has no resonators
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Process one sample: couple every string into every other string
+    /// and return the bank's summed output
+    pub fn process(&mut self) -> f32 {
+        let total: f32 = self.last_outputs.iter().sum();
+
+        let mut mix = 0.0f32;
+        for (i, string) in self.strings.iter_mut().enumerate() {
+            let coupling_excitation = self.coupling_gain * (total - self.last_outputs[i]);
+            let output = string.process(coupling_excitation);
+            self.last_outputs[i] = output;
+            mix += output;
+        }
+        mix
+    }
+}
+
+impl Default for SympatheticBank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,9 +2,191 @@
 //!
 //! Handles up to 128 simultaneous voices with voice stealing
 //! when the pool is exhausted.
+//!
+//! ## Sample-accurate scheduling
+//!
+//! `tick` alone only advances ages by a whole block, so note on/off can
+//! only land on block boundaries. The timestamped event queue
+//! (`push_next`/`pop_next`/`peek_clock`) and `process_block` let a host
+//! enqueue events at arbitrary sample offsets within the next block;
+//! `process_block` drains them in order, advancing ages per-sample
+//! between events so timing (and voice-stealing priority) is accurate
+//! to the sample, and hands each event's offset back to the caller so
+//! it can start the resulting voice's audio exactly there instead of
+//! at the top of the block.
+
+use std::collections::VecDeque;
 
 use crate::MAX_VOICES;
 
+/// A note event the host can schedule at a precise sample offset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoteEvent {
+    /// Trigger a new voice on the string for `midi_note`'s pitch class
+    NoteOn { midi_note: u8, velocity: f32 },
+    /// Release all voices currently playing `midi_note`
+    NoteOff { midi_note: u8 },
+}
+
+/// Which segment of the ADSR curve an `Envelope` is currently in
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EnvelopeStage {
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+    /// Fully decayed; the voice is free to be recycled
+    Idle,
+}
+
+/// Per-voice linear-segment ADSR amplitude envelope
+#[derive(Clone, Copy)]
+pub struct Envelope {
+    attack_samples: u32,
+    decay_samples: u32,
+    sustain_level: f32,
+    release_samples: u32,
+    stage: EnvelopeStage,
+    /// Sample count into the current stage
+    position: u32,
+    /// Current output level (0-1)
+    level: f32,
+    /// Level captured when release began, so the release segment ramps
+    /// down from wherever the envelope actually was (not always 1.0)
+    release_start_level: f32,
+}
+
+impl Envelope {
+    /// Create an envelope with short-attack, plucked-string-like defaults
+    pub fn new(sample_rate: f32) -> Self {
+        let mut envelope = Envelope {
+            attack_samples: 0,
+            decay_samples: 0,
+            sustain_level: 0.8,
+            release_samples: 0,
+            stage: EnvelopeStage::Idle,
+            position: 0,
+            level: 0.0,
+            release_start_level: 0.0,
+        };
+        envelope.set_attack(0.005, sample_rate);
+        envelope.set_decay(0.1, sample_rate);
+        envelope.set_release(0.3, sample_rate);
+        envelope
+    }
+
+    /// Set attack time in seconds
+    pub fn set_attack(&mut self, seconds: f32, sample_rate: f32) {
+        self.attack_samples = (seconds.max(0.0) * sample_rate) as u32;
+    }
+
+    /// Set decay time in seconds
+    pub fn set_decay(&mut self, seconds: f32, sample_rate: f32) {
+        self.decay_samples = (seconds.max(0.0) * sample_rate) as u32;
+    }
+
+    /// Set sustain level (0-1)
+    pub fn set_sustain(&mut self, level: f32) {
+        self.sustain_level = level.clamp(0.0, 1.0);
+    }
+
+    /// Set release time in seconds
+    pub fn set_release(&mut self, seconds: f32, sample_rate: f32) {
+        self.release_samples = (seconds.max(0.0) * sample_rate) as u32;
+    }
+
+    /// (Re)trigger the envelope from the start of the attack segment
+    pub fn trigger(&mut self) {
+        self.stage = EnvelopeStage::Attack;
+        self.position = 0;
+        self.level = 0.0;
+    }
+
+    /// Move the envelope into its release segment, ramping down from
+    /// whatever level it was at (not necessarily the sustain level)
+    pub fn note_off(&mut self) {
+        self.release_start_level = self.level;
+        self.stage = EnvelopeStage::Release;
+        self.position = 0;
+    }
+
+    /// True once the release segment has fully decayed to zero
+    pub fn is_finished(&self) -> bool {
+        self.stage == EnvelopeStage::Idle
+    }
+
+    /// True while the envelope is in its release segment
+    pub fn is_releasing(&self) -> bool {
+        self.stage == EnvelopeStage::Release
+    }
+
+    /// Current output level (0-1), as of the last `advance` call
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Advance the envelope by one sample and return its current level
+    pub fn advance(&mut self) -> f32 {
+        match self.stage {
+            EnvelopeStage::Attack => {
+                if self.attack_samples == 0 {
+                    self.level = 1.0;
+                    self.stage = EnvelopeStage::Decay;
+                    self.position = 0;
+                } else {
+                    self.level = self.position as f32 / self.attack_samples as f32;
+                    self.position += 1;
+                    if self.position >= self.attack_samples {
+                        self.stage = EnvelopeStage::Decay;
+                        self.position = 0;
+                    }
+                }
+            }
+            EnvelopeStage::Decay => {
+                if self.decay_samples == 0 {
+                    self.level = self.sustain_level;
+                    self.stage = EnvelopeStage::Sustain;
+                } else {
+                    let t = (self.position as f32 / self.decay_samples as f32).min(1.0);
+                    self.level = 1.0 + (self.sustain_level - 1.0) * t;
+                    self.position += 1;
+                    if self.position >= self.decay_samples {
+                        self.stage = EnvelopeStage::Sustain;
+                        self.position = 0;
+                    }
+                }
+            }
+            EnvelopeStage::Sustain => {
+                self.level = self.sustain_level;
+            }
+            EnvelopeStage::Release => {
+                if self.release_samples == 0 {
+                    self.level = 0.0;
+                    self.stage = EnvelopeStage::Idle;
+                } else {
+                    let t = (self.position as f32 / self.release_samples as f32).min(1.0);
+                    self.level = self.release_start_level * (1.0 - t);
+                    self.position += 1;
+                    if self.position >= self.release_samples {
+                        self.level = 0.0;
+                        self.stage = EnvelopeStage::Idle;
+                    }
+                }
+            }
+            EnvelopeStage::Idle => {
+                self.level = 0.0;
+            }
+        }
+        self.level
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self::new(crate::SAMPLE_RATE)
+    }
+}
+
 /// State of a single voice
 #[derive(Clone, Copy)]
 pub struct Voice {
@@ -20,6 +202,12 @@ pub struct Voice {
     pub release_time: u32,
     /// Is voice in release phase?
     pub releasing: bool,
+    /// True MIDI note this voice is tuned to (may span octaves, unlike
+    /// `string_index` which is always the note's pitch class 0-11)
+    pub note: u8,
+    /// Per-voice amplitude envelope, initialized from the pool's shared
+    /// ADSR settings each time this voice is allocated
+    pub envelope: Envelope,
 }
 
 impl Default for Voice {
@@ -31,6 +219,8 @@ impl Default for Voice {
             age: 0,
             release_time: 0,
             releasing: false,
+            note: 0,
+            envelope: Envelope::default(),
         }
     }
 }
@@ -43,6 +233,12 @@ pub struct VoicePool {
     max_voices: usize,
     /// Current number of active voices
     active_count: usize,
+    /// Shared ADSR settings applied to every voice's envelope when it is
+    /// (re)allocated; set via `set_attack`/`set_decay`/`set_sustain`/`set_release`
+    envelope_template: Envelope,
+    /// Pending note events, sorted ascending by sample offset within the
+    /// next block (the offset is relative to the start of that block)
+    event_queue: VecDeque<(u32, NoteEvent)>,
 }
 
 impl VoicePool {
@@ -53,9 +249,31 @@ impl VoicePool {
             voices: vec![Voice::default(); max_voices],
             max_voices,
             active_count: 0,
+            envelope_template: Envelope::default(),
+            event_queue: VecDeque::new(),
         }
     }
 
+    /// Set attack time (seconds) for voices allocated from now on
+    pub fn set_attack(&mut self, seconds: f32) {
+        self.envelope_template.set_attack(seconds, crate::SAMPLE_RATE);
+    }
+
+    /// Set decay time (seconds) for voices allocated from now on
+    pub fn set_decay(&mut self, seconds: f32) {
+        self.envelope_template.set_decay(seconds, crate::SAMPLE_RATE);
+    }
+
+    /// Set sustain level (0-1) for voices allocated from now on
+    pub fn set_sustain(&mut self, level: f32) {
+        self.envelope_template.set_sustain(level);
+    }
+
+    /// Set release time (seconds) for voices allocated from now on
+    pub fn set_release(&mut self, seconds: f32) {
+        self.envelope_template.set_release(seconds, crate::SAMPLE_RATE);
+    }
+
     /// Allocate a new voice for the given string
     ///
     /// Returns the voice ID if successful, or None if pool is exhausted
@@ -69,6 +287,8 @@ impl VoicePool {
                 voice.age = 0;
                 voice.release_time = 0;
                 voice.releasing = false;
+                voice.envelope = self.envelope_template;
+                voice.envelope.trigger();
                 self.active_count += 1;
                 return Some(i);
             }
@@ -91,6 +311,8 @@ impl VoicePool {
             voice.age = 0;
             voice.release_time = 0;
             voice.releasing = false;
+            voice.envelope = self.envelope_template;
+            voice.envelope.trigger();
             return Some(idx);
         }
 
@@ -110,6 +332,8 @@ impl VoicePool {
         voice.age = 0;
         voice.release_time = 0;
         voice.releasing = false;
+        voice.envelope = self.envelope_template;
+        voice.envelope.trigger();
         Some(oldest_idx)
     }
 
@@ -125,11 +349,30 @@ impl VoicePool {
         }
     }
 
+    /// Set the true MIDI note a voice is tuned to (independent of its
+    /// `string_index`/pitch class, for voices that span octaves)
+    pub fn set_note(&mut self, voice_id: usize, note: u8) {
+        if voice_id < self.voices.len() {
+            self.voices[voice_id].note = note;
+        }
+    }
+
+    /// Get all active voice IDs, regardless of string
+    pub fn active_voice_ids(&self) -> Vec<usize> {
+        self.voices
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.active)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
     /// Release a voice (start release phase)
     pub fn release(&mut self, voice_id: usize) {
         if voice_id < self.voices.len() {
             self.voices[voice_id].releasing = true;
             self.voices[voice_id].release_time = 0;
+            self.voices[voice_id].envelope.note_off();
         }
     }
 
@@ -139,6 +382,7 @@ impl VoicePool {
             if voice.active && voice.string_index == string_index {
                 voice.releasing = true;
                 voice.release_time = 0;
+                voice.envelope.note_off();
             }
         }
     }
@@ -168,6 +412,77 @@ impl VoicePool {
         self.active_count
     }
 
+    /// Schedule `event` to take effect `sample_offset` samples into the
+    /// next block, keeping the queue sorted by offset
+    pub fn push_next(&mut self, sample_offset: u32, event: NoteEvent) {
+        let insert_at = self
+            .event_queue
+            .iter()
+            .position(|(offset, _)| *offset > sample_offset)
+            .unwrap_or(self.event_queue.len());
+        self.event_queue.insert(insert_at, (sample_offset, event));
+    }
+
+    /// Pop the next scheduled event in offset order
+    pub fn pop_next(&mut self) -> Option<(u32, NoteEvent)> {
+        self.event_queue.pop_front()
+    }
+
+    /// Sample offset of the next scheduled event, if any
+    pub fn peek_clock(&self) -> Option<u32> {
+        self.event_queue.front().map(|(offset, _)| *offset)
+    }
+
+    /// Drain all events scheduled within the next `block_len` samples,
+    /// ticking voice ages between them so ages and stealing priority stay
+    /// accurate to the sample, and apply each one (`allocate`/`release`).
+    ///
+    /// `on_event` is called for every applied event with its sample
+    /// offset, the event itself, and the voice ID it affected (the first
+    /// voice released, for `NoteOff`), so the caller can start or stop
+    /// that voice's audio at the exact offset instead of at the top of
+    /// the block.
+    pub fn process_block<F: FnMut(u32, &NoteEvent, Option<usize>)>(
+        &mut self,
+        block_len: u32,
+        mut on_event: F,
+    ) {
+        let mut clock = 0u32;
+        while let Some(offset) = self.peek_clock() {
+            if offset >= block_len {
+                break;
+            }
+            self.tick(offset - clock);
+            clock = offset;
+            let (_, event) = self.pop_next().expect("peek_clock just confirmed an event");
+            let voice_id = match event {
+                NoteEvent::NoteOn { midi_note, velocity } => {
+                    let string_index = (midi_note as usize) % crate::NUM_STRINGS;
+                    let id = self.allocate(string_index);
+                    if let Some(id) = id {
+                        self.voices[id].velocity = velocity;
+                        self.voices[id].note = midi_note;
+                    }
+                    id
+                }
+                NoteEvent::NoteOff { midi_note } => {
+                    let mut first = None;
+                    for i in 0..self.voices.len() {
+                        if self.voices[i].active && self.voices[i].note == midi_note {
+                            self.release(i);
+                            if first.is_none() {
+                                first = Some(i);
+                            }
+                        }
+                    }
+                    first
+                }
+            };
+            on_event(offset, &event, voice_id);
+        }
+        self.tick(block_len - clock);
+    }
+
     /// Get all active voice IDs for a given string
     pub fn get_voices_for_string(&self, string_index: usize) -> Vec<usize> {
         self.voices
@@ -183,6 +498,11 @@ impl VoicePool {
         self.voices.get(voice_id)
     }
 
+    /// Get mutable voice info (e.g. to advance its envelope)
+    pub fn get_voice_mut(&mut self, voice_id: usize) -> Option<&mut Voice> {
+        self.voices.get_mut(voice_id)
+    }
+
     /// Clear all voices
     pub fn clear(&mut self) {
         for voice in &mut self.voices {